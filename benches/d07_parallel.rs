@@ -0,0 +1,71 @@
+/*
+Day 7 checked one line at a time against spreading the lines across threads,
+on a large made-up input so there's enough work to split.
+
+Needs the `parallel` feature: `cargo bench --features parallel -- d07_parallel`.
+*/
+use std::time::Duration;
+
+use aoc_rs_24::{
+    d07::{self, Line, Operator, Rules},
+    solution::Part,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Lines like the puzzle's: results built from a fixed mix of operators, so
+/// most of them have a solution, and every fourth one nudged so it probably
+/// doesn't.
+fn synthetic(n_lines: usize) -> Vec<Line> {
+    let operators = [Operator::Add, Operator::Mul, Operator::Concat];
+
+    let mut lines = Vec::with_capacity(n_lines);
+    for i in 0.. {
+        if lines.len() == n_lines {
+            break;
+        }
+        let n_operands = 3 + i % 10;
+        let operands: Vec<u64> = (0..n_operands)
+            .map(|j| 1 + (i * 7 + j * 13) as u64 % 99)
+            .collect();
+        let result = operands[1..]
+            .iter()
+            .enumerate()
+            .try_fold(operands[0], |acc, (j, &operand)| {
+                operators[(i * 5 + j) % 3].apply(acc, operand)
+            });
+        // Skip the ones that don't fit in a u64.
+        if let Some(result) = result {
+            let result = if lines.len() % 4 == 0 {
+                result + 1
+            } else {
+                result
+            };
+            lines.push(Line { result, operands });
+        }
+    }
+    lines
+}
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    let lines = synthetic(10_000);
+    let rules = Rules::for_part(Part::Two);
+    assert_eq!(
+        d07::sum_valid_lines(&lines, &rules),
+        d07::sum_valid_lines_parallel(&lines, &rules, None),
+        "Serial and parallel should agree."
+    );
+
+    let mut group = c.benchmark_group("d07_parallel");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.bench_function("serial", |b| {
+        b.iter(|| d07::sum_valid_lines(black_box(&lines), &rules))
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| d07::sum_valid_lines_parallel(black_box(&lines), &rules, None))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
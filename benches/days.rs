@@ -0,0 +1,60 @@
+/*
+Benchmarks for every registered day: parsing, then each part that's
+implemented, grouped per day (`d01/parse`, `d01/part1`, ...).
+
+Days without an input in `inputs/` are skipped, as are parts that aren't
+implemented or fail. Use criterion's filter to run a subset, e.g.
+`cargo bench -- d07`.
+*/
+use std::{fs, panic, path::Path, time::Duration};
+
+use aoc_rs_24::{
+    inputs,
+    runner::{self, Status},
+    solution::DAYS,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+pub fn criterion_benchmark(c: &mut Criterion) {
+    for day in DAYS.iter() {
+        let Ok(input) = fs::read_to_string(inputs::path(Path::new(inputs::DEFAULT_DIR), day.day))
+        else {
+            eprintln!("Skipping day {}: no input", day.day);
+            continue;
+        };
+
+        // Run each part once first, to find out which ones are worth timing.
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let records = runner::run_day(day, &input);
+        panic::set_hook(hook);
+
+        // Only successful parses are timed; a failed one may panic again.
+        if records[0].parse_time.is_none() {
+            eprintln!("Skipping day {}: {}", day.day, records[0].status);
+            continue;
+        }
+        let parsed = day.parse(&input).expect("It parsed a moment ago.");
+
+        let mut group = c.benchmark_group(format!("d{:02}", day.day));
+        // Some parts take seconds per iteration.
+        group.sample_size(10);
+        group.measurement_time(Duration::from_secs(5));
+
+        group.bench_function("parse", |b| b.iter(|| day.parse(black_box(&input))));
+        for record in records {
+            match record.status {
+                Status::Solved(_) => {
+                    group.bench_function(format!("part{}", record.part), |b| {
+                        b.iter(|| day.solve(black_box(&parsed), record.part))
+                    });
+                }
+                status => eprintln!("Skipping day {} part {}: {status}", day.day, record.part),
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
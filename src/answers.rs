@@ -0,0 +1,234 @@
+/*
+Known answers, so that a refactor of any day gets caught as a regression.
+
+The answers file has one `day part answer` entry per line. Prefix the answer
+with `!` to record a value we know is wrong (e.g. one the site rejected as
+too high); a day can have several of those alongside its expected answer.
+
+    # day part answer
+    3 1 160672468
+    3 2 !84893551
+*/
+use std::{
+    collections::HashMap,
+    error, fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::solution::{Answer, Part};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    KnownWrong,
+    Unknown,
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::KnownWrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => f.pad("PASS"),
+            Verdict::Fail { .. } => f.pad("FAIL"),
+            Verdict::KnownWrong => f.pad("KNOWN-WRONG"),
+            Verdict::Unknown => f.pad("-"),
+        }
+    }
+}
+
+/// A line of the answers file that isn't `day part answer`. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl error::Error for Error {}
+
+#[derive(Debug, Default)]
+pub struct Answers {
+    pub expected: HashMap<(u8, Part), String>,
+    pub wrong: HashMap<(u8, Part), Vec<String>>,
+}
+
+pub fn parse_input(input: &str) -> Result<Answers, Error> {
+    let mut answers = Answers::default();
+
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: String| Error {
+            line: i + 1,
+            message,
+        };
+        let line = line
+            .split_once('#')
+            .map_or(line, |(before, _)| before)
+            .trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line.split_ascii_whitespace();
+        let (Some(day), Some(part), Some(answer), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(error(format!("expected 'day part answer', found {line:?}")));
+        };
+
+        let day = day
+            .parse()
+            .map_err(|_| error(format!("invalid day {day:?}")))?;
+        let part: Part = part.parse().map_err(error)?;
+
+        if let Some(wrong) = answer.strip_prefix('!') {
+            answers
+                .wrong
+                .entry((day, part))
+                .or_default()
+                .push(wrong.to_string());
+        } else {
+            answers.expected.insert((day, part), answer.to_string());
+        }
+    }
+
+    Ok(answers)
+}
+
+impl Answers {
+    /// Loads the answers file, treating a missing file as having no answers.
+    pub fn load(path: &Path) -> Result<Answers, Error> {
+        match fs::read_to_string(path) {
+            Ok(input) => parse_input(&input),
+            Err(_) => Ok(Answers::default()),
+        }
+    }
+
+    /// Appends an answer to the answers file, as known wrong unless `correct`.
+    pub fn record(
+        path: &Path,
+        day: u8,
+        part: Part,
+        answer: &Answer,
+        correct: bool,
+    ) -> io::Result<()> {
+        let existing = fs::read_to_string(path).unwrap_or_default();
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if !existing.is_empty() && !existing.ends_with('\n') {
+            writeln!(file)?;
+        }
+        let prefix = if correct { "" } else { "!" };
+        writeln!(file, "{day} {part} {prefix}{answer}")
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &Answer) -> Verdict {
+        let answer = answer.to_string();
+
+        if let Some(expected) = self.expected.get(&(day, part)) {
+            if *expected == answer {
+                return Verdict::Pass;
+            }
+        }
+        if self
+            .wrong
+            .get(&(day, part))
+            .is_some_and(|wrong| wrong.contains(&answer))
+        {
+            return Verdict::KnownWrong;
+        }
+
+        match self.expected.get(&(day, part)) {
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::{Answer, Part};
+
+    use super::{parse_input, Answers, Verdict};
+
+    const INPUT: &str = "\
+# day part answer
+1 1 11
+1 2 31  # trailing comment
+
+3 2 !84893551
+17 1 4,6,3,5,6,3,5,2,1,0";
+
+    #[test]
+    fn test_check() {
+        let answers = parse_input(INPUT).unwrap();
+        assert_eq!(answers.expected.len(), 3);
+
+        assert_eq!(
+            answers.check(1, Part::One, &Answer::Number(11)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, &Answer::Number(30)),
+            Verdict::Fail {
+                expected: "31".to_string()
+            }
+        );
+        assert_eq!(
+            answers.check(3, Part::Two, &Answer::Number(84893551)),
+            Verdict::KnownWrong
+        );
+        assert_eq!(
+            answers.check(3, Part::Two, &Answer::Number(1)),
+            Verdict::Unknown
+        );
+        assert_eq!(
+            answers.check(17, Part::One, &Answer::Text("4,6,3,5,6,3,5,2,1,0".into())),
+            Verdict::Pass
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.txt", std::process::id()));
+        std::fs::write(&path, "1 1 11").unwrap();
+
+        Answers::record(&path, 1, Part::Two, &Answer::Number(30), false).unwrap();
+        Answers::record(&path, 1, Part::Two, &Answer::Number(31), true).unwrap();
+
+        let answers = Answers::load(&path).unwrap();
+        assert_eq!(
+            answers.check(1, Part::Two, &Answer::Number(30)),
+            Verdict::KnownWrong
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, &Answer::Number(31)),
+            Verdict::Pass
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let error = parse_input("1 1 11\n1 1").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected 'day part answer', found \"1 1\""
+        );
+        assert_eq!(parse_input("x 1 11").unwrap_err().line, 1);
+        assert_eq!(parse_input("1 3 11").unwrap_err().line, 1);
+    }
+}
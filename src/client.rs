@@ -0,0 +1,387 @@
+/*
+Talking to the puzzle site: download a day's input and submit answers.
+
+The session token is the `session` cookie of a logged-in browser, read from
+`AOC_SESSION`. Inputs are cached in the inputs directory (as `NN.txt`, the
+same place everything else looks for them), so each one is downloaded once.
+Requests are spaced at least `MIN_INTERVAL` apart, tracked through a file in
+that directory so that it holds across separate runs of the binary too.
+
+The base URL is configurable so tests can point the client at a local server.
+*/
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    inputs,
+    solution::{Answer, Part},
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const YEAR: u16 = 2024;
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+const LAST_REQUEST_FILE: &str = ".last-request";
+
+#[derive(Debug)]
+pub enum Error {
+    MissingSession,
+    Status {
+        url: String,
+        code: u16,
+        body: String,
+    },
+    Transport(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingSession => write!(
+                f,
+                "{SESSION_VAR} isn't set; set it to the session cookie of a logged-in browser"
+            ),
+            Error::Status { url, code, body } => {
+                write!(f, "{url} returned {code}")?;
+                match body.lines().next() {
+                    Some(line) if !line.is_empty() => write!(f, ": {line}"),
+                    _ => Ok(()),
+                }
+            }
+            Error::Transport(message) => write!(f, "{message}"),
+            Error::Io(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<ureq::Error> for Error {
+    fn from(err: ureq::Error) -> Self {
+        match err {
+            ureq::Error::Status(code, response) => Error::Status {
+                url: response.get_url().to_string(),
+                code,
+                body: response.into_string().unwrap_or_default(),
+            },
+            ureq::Error::Transport(transport) => Error::Transport(transport.to_string()),
+        }
+    }
+}
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    /// With the site's hint, "too high" or "too low", when it gives one.
+    Incorrect(Option<String>),
+    /// Submitted too soon after the last answer; the site says how long to wait.
+    TooSoon(Option<String>),
+    /// The part is already solved, or isn't unlocked yet.
+    WrongLevel,
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Classifies the page returned for a submission.
+    pub fn from_response(body: &str) -> Outcome {
+        if body.contains("That's the right answer") {
+            Outcome::Correct
+        } else if body.contains("That's not the right answer") {
+            let hint = ["too high", "too low"]
+                .into_iter()
+                .find(|hint| body.contains(hint));
+            Outcome::Incorrect(hint.map(String::from))
+        } else if body.contains("You gave an answer too recently") {
+            let wait = body
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map(|(wait, _)| wait.to_string());
+            Outcome::TooSoon(wait)
+        } else if body.contains("You don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown(body.to_string())
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "Correct!"),
+            Outcome::Incorrect(Some(hint)) => write!(f, "Incorrect ({hint})"),
+            Outcome::Incorrect(None) => write!(f, "Incorrect"),
+            Outcome::TooSoon(Some(wait)) => write!(f, "Too soon, wait {wait}"),
+            Outcome::TooSoon(None) => write!(f, "Too soon"),
+            Outcome::WrongLevel => write!(f, "Already solved, or not unlocked yet"),
+            Outcome::Unknown(_) => write!(f, "Unrecognized response"),
+        }
+    }
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, cache_dir: &Path) -> Client {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            cache_dir: cache_dir.to_path_buf(),
+            min_interval: MIN_INTERVAL,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// Like `new`, with the session token from `AOC_SESSION`.
+    pub fn from_env(base_url: &str, cache_dir: &Path) -> Result<Client, Error> {
+        match std::env::var(SESSION_VAR) {
+            Ok(session) if !session.trim().is_empty() => {
+                Ok(Client::new(base_url, &session, cache_dir))
+            }
+            _ => Err(Error::MissingSession),
+        }
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Client {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// The day's input, from the cache if it's there and downloaded (and
+    /// cached) otherwise.
+    pub fn fetch_input(&self, day: u8) -> Result<String, Error> {
+        let path = inputs::path(&self.cache_dir, day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+
+        self.wait_for_turn()?;
+        let input = self
+            .agent
+            .get(&format!("{}/{YEAR}/day/{day}/input", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .call()?
+            .into_string()?;
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(&path, &input)?;
+        Ok(input)
+    }
+
+    pub fn submit(&self, day: u8, part: Part, answer: &Answer) -> Result<Outcome, Error> {
+        self.wait_for_turn()?;
+        let body = self
+            .agent
+            .post(&format!("{}/{YEAR}/day/{day}/answer", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[
+                ("level", &part.number().to_string()),
+                ("answer", &answer.to_string()),
+            ])?
+            .into_string()?;
+        Ok(Outcome::from_response(&body))
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request (from
+    /// any run), then records this one.
+    fn wait_for_turn(&self) -> Result<(), Error> {
+        let marker = self.cache_dir.join(LAST_REQUEST_FILE);
+        let now = || {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Clock is before 1970")
+        };
+
+        let last = fs::read_to_string(&marker)
+            .ok()
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis);
+        if let Some(last) = last {
+            if let Some(wait) = (last + self.min_interval).checked_sub(now()) {
+                thread::sleep(wait);
+            }
+        }
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(&marker, now().as_millis().to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread::{self, JoinHandle},
+        time::{Duration, Instant},
+    };
+
+    use crate::solution::{Answer, Part};
+
+    use super::{Client, Error, Outcome};
+
+    /// A stand-in for the site: answers each connection with the next of
+    /// `responses` and hands back the requests it got, once they're all used.
+    fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (code, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body_in = vec![0; content_length];
+                reader.read_exact(&mut body_in).unwrap();
+                request.push_str(&String::from_utf8(body_in).unwrap());
+                requests.push(request);
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {code} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-client-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetch_input() {
+        let (url, server) = serve(vec![(200, "3   4\n4   3\n")]);
+        let dir = cache_dir("fetch");
+        let client = Client::new(&url, "secret\n", &dir).with_min_interval(Duration::ZERO);
+
+        assert_eq!(client.fetch_input(1).unwrap(), "3   4\n4   3\n");
+        // The second time comes from the cache; the server would hang up.
+        assert_eq!(client.fetch_input(1).unwrap(), "3   4\n4   3\n");
+        assert_eq!(
+            fs::read_to_string(dir.join("01.txt")).unwrap(),
+            "3   4\n4   3\n"
+        );
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2024/day/1/input HTTP/1.1"));
+        assert!(requests[0].contains("session=secret\r\n"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let (url, server) = serve(vec![(404, "Please don't repeatedly request this endpoint")]);
+        let dir = cache_dir("error");
+        let client = Client::new(&url, "secret", &dir).with_min_interval(Duration::ZERO);
+
+        let err = client.fetch_input(25).unwrap_err();
+        assert!(matches!(err, Error::Status { code: 404, .. }));
+        assert!(err
+            .to_string()
+            .ends_with("returned 404: Please don't repeatedly request this endpoint"));
+        assert!(!dir.join("25.txt").exists());
+
+        server.join().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, server) = serve(vec![
+            (
+                200,
+                "<p>That's not the right answer; your answer is too high.</p>",
+            ),
+            (200, "<p>That's the right answer!</p>"),
+        ]);
+        let dir = cache_dir("submit");
+        let client =
+            Client::new(&url, "secret", &dir).with_min_interval(Duration::from_millis(200));
+
+        let start = Instant::now();
+        assert_eq!(
+            client
+                .submit(3, Part::Two, &Answer::Number(84893551))
+                .unwrap(),
+            Outcome::Incorrect(Some("too high".into()))
+        );
+        assert_eq!(
+            client
+                .submit(3, Part::Two, &Answer::Number(84893550))
+                .unwrap(),
+            Outcome::Correct
+        );
+        // Rate limited.
+        assert!(start.elapsed() >= Duration::from_millis(200));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2024/day/3/answer HTTP/1.1"));
+        assert!(requests[0].ends_with("level=2&answer=84893551"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(
+            Outcome::from_response(
+                "You gave an answer too recently. You have 4m 30s left to wait."
+            ),
+            Outcome::TooSoon(Some("4m 30s".into()))
+        );
+        assert_eq!(
+            Outcome::from_response("You don't seem to be solving the right level."),
+            Outcome::WrongLevel
+        );
+        assert!(matches!(
+            Outcome::from_response("<html>"),
+            Outcome::Unknown(_)
+        ));
+    }
+}
@@ -1,11 +1,21 @@
 use std::collections;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub struct Lists {
     pub left: Vec<u32>,
     pub right: Vec<u32>,
 }
 
 pub fn parse_input(input: &str) -> Lists {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Lists, ParseError> {
+    let source = Source::new(1, input);
     // Seems like just using Vecs and then
     // sorting is slightly faster on the benchmark?
     // let mut left = collections::BinaryHeap::new();
@@ -16,22 +26,19 @@ pub fn parse_input(input: &str) -> Lists {
     for line in input.lines() {
         let mut iter = line.split_ascii_whitespace();
         // iter.count()
-        left.push(
-            iter.next()
-                .expect("Invalid line")
-                .parse()
-                .expect("Failed to parse number"),
-        );
-        right.push(iter.next().unwrap().parse().unwrap());
-
-        assert!(iter.next().is_none());
+        left.push(source.number(source.next(&mut iter, line, "two numbers")?)?);
+        right.push(source.number(source.next(&mut iter, line, "two numbers")?)?);
+
+        if let Some(extra) = iter.next() {
+            return Err(source.error(extra, "expected two numbers"));
+        }
     }
 
     left.sort();
     right.sort();
 
     // Lists { left: left.into_sorted_vec(), right: right.into_sorted_vec() }
-    Lists { left, right }
+    Ok(Lists { left, right })
 }
 
 pub fn find_distance(lists: &Lists) -> i64 {
@@ -64,6 +71,24 @@ pub fn similarity_score(lists: &Lists) -> i64 {
     score
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Lists;
+
+    fn parse(input: &str) -> Result<Lists, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Lists) -> Option<Answer> {
+        Some((find_distance(input) as u64).into())
+    }
+
+    fn part2(input: &Lists) -> Option<Answer> {
+        Some((similarity_score(input) as u64).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d01::{find_distance, parse_input, similarity_score};
@@ -1,9 +1,19 @@
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub fn parse_input(input: &str) -> Vec<Vec<u32>> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+    let source = Source::new(2, input);
     input
         .lines()
         .map(|line| {
             line.split_ascii_whitespace()
-                .map(|c| c.parse().expect("Failed to parse digit from record."))
+                .map(|c| source.number(c))
                 .collect()
         })
         .collect()
@@ -78,6 +88,24 @@ pub fn count_safe(report: &[Vec<u32>], damped: bool) -> usize {
         .count()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Vec<u32>>;
+
+    fn parse(input: &str) -> Result<Vec<Vec<u32>>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Vec<Vec<u32>>) -> Option<Answer> {
+        Some(count_safe(input, false).into())
+    }
+
+    fn part2(input: &Vec<Vec<u32>>) -> Option<Answer> {
+        Some(count_safe(input, true).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d02::{is_safe_damped, parse_input};
@@ -1,5 +1,10 @@
 use regex::Regex;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::ParseError,
+};
+
 pub fn evaluate(input: &str) -> i32 {
     let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
 
@@ -25,6 +30,25 @@ pub fn evaluate_switches(input: &str) -> i32 {
         .sum()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = String;
+
+    // Anything goes: the corrupted memory is mostly noise anyway.
+    fn parse(input: &str) -> Result<String, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &String) -> Option<Answer> {
+        Some((evaluate(input) as u64).into())
+    }
+
+    fn part2(input: &String) -> Option<Answer> {
+        Some((evaluate_switches(input) as u64).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d03::{evaluate, evaluate_switches};
@@ -25,7 +25,21 @@ Alternate: just search forwards, backwards, and all the diagonals?
 
 */
 
-pub fn generate_slices(input: &str) -> Vec<String> {
+use crate::{
+    solution::{Answer, Solution},
+    utils::{Grid, ParseError, Source},
+};
+
+pub fn parse_input(input: &str) -> Grid<char> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    let (grid, _) = Grid::parse(&Source::new(4, input), input, &[], Some)?;
+    Ok(grid)
+}
+
+pub fn generate_slices(grid: &Grid<char>) -> Vec<String> {
     /*
 
     1. lines forward
@@ -37,93 +51,46 @@ pub fn generate_slices(input: &str) -> Vec<String> {
 
     */
 
-    // TODO: figure out how to yield these?
-    // TODO: we should know the capacity from input.
-    let mut horizontal: Vec<String> = Vec::new();
-    let mut vertical: Vec<String> = Vec::new();
-    let mut diagonal_lr: Vec<String> = Vec::new();
-    let mut diagonal_rl: Vec<String> = Vec::new();
-
-    for (i, line) in input.lines().enumerate() {
-        let n = line.len();
-
-        // horizontal (reversible). Append each line.
-        horizontal.push(String::from(line));
-
-        for (j, c) in line.chars().enumerate() {
-            // vertical (reversible).
-            if i == 0 {
-                vertical.push(String::from(c));
-            } else {
-                vertical[j].push(c);
-            }
-
-            // handle all the LR first
-
-            // diagonal.
-            // There are M + (N - 1) diagonals in an (MxN) matrix
-            if i == 0 || j == 0 {
-                diagonal_lr.push(String::from(c));
-            } else if j >= i {
-                // Extend a diagonal that started on the first row
-                diagonal_lr[j - i].push(c);
-            } else {
-                // Extend a diagonal that started on the i-th row.
-                // For example, at position [2, 1] (row 3, col 2) of a 3x4 matrix
-                // we have i=2, j=1, n=4. Our string is at position 4 in the vector.
-                // i - j + n - 1 = 2 - 1 + 4 - 1 = 4.
-                // See if there's a general formula?
-                diagonal_lr[i - j + n - 1].push(c);
-            }
-
-            // Handle all the RL
-            // eprintln!("i={i} j={j} n={n}");
-            if i == 0 || j == (n - 1) {
-                // create a new diagonal start on ...
-                diagonal_rl.push(String::from(c));
-            } else {
-                diagonal_rl[i + j].push(c);
-            }
-        }
-    }
+    let horizontal = grid.rows().map(|row| row.iter().collect());
+    let vertical = grid.columns().map(|column| column.collect());
+    let diagonal_lr = grid.diagonals().map(|diagonal| diagonal.collect());
+    let diagonal_rl = grid.anti_diagonals().map(|diagonal| diagonal.collect());
 
-    // horizontal.iter().chain(vertical).it
-    [horizontal, vertical, diagonal_lr, diagonal_rl].concat()
+    horizontal
+        .chain(vertical)
+        .chain(diagonal_lr)
+        .chain(diagonal_rl)
+        .collect()
 }
 
-pub fn count_mas_xs(input: &str) -> usize {
-    // Look in the window around us
+pub fn count_mas_xs(grid: &Grid<char>) -> usize {
+    // Look at the corners around each 'A'
     let mut count = 0;
-    let lines: Vec<_> = input.lines().collect();
-    let windows = lines.windows(3);
-    let n = lines[0].len();
-
-    for window in windows {
-        for (j, char) in window[1].chars().enumerate() {
-            if char == 'A' && (1..n - 1).contains(&j) {
-                let a = window[0].as_bytes()[j - 1]; // M / 77
-                let b = window[0].as_bytes()[j + 1]; // S / 83
-                let c = window[2].as_bytes()[j - 1];
-                let d = window[2].as_bytes()[j + 1];
-
-                let xx = (a == b'M' && d == b'S') || (a == b'S' && d == b'M');
-                let yy = (b == b'M' && c == b'S') || (b == b'S' && c == b'M');
-
-                if xx && yy {
-                    count += 1;
-                }
-            }
+
+    for ((row, col), &char) in grid.iter() {
+        if char != 'A' || row == 0 || col == 0 {
+            continue;
+        }
+        let corner = |r: usize, c: usize| grid.iloc(r, c).copied();
+        let (a, b) = (corner(row - 1, col - 1), corner(row - 1, col + 1));
+        let (c, d) = (corner(row + 1, col - 1), corner(row + 1, col + 1));
+
+        let xx = (a == Some('M') && d == Some('S')) || (a == Some('S') && d == Some('M'));
+        let yy = (b == Some('M') && c == Some('S')) || (b == Some('S') && c == Some('M'));
+
+        if xx && yy {
+            count += 1;
         }
     }
 
     count
 }
 
-pub fn count_xmas(input: &str, xs: bool) -> usize {
+pub fn count_xmas_in(grid: &Grid<char>, xs: bool) -> usize {
     if xs {
-        count_mas_xs(input)
+        count_mas_xs(grid)
     } else {
-        let sequences = generate_slices(input);
+        let sequences = generate_slices(grid);
         // eprintln!("{sequences:#?}");
         let mut count = 0;
         for sequence in sequences {
@@ -134,6 +101,28 @@ pub fn count_xmas(input: &str, xs: bool) -> usize {
     }
 }
 
+pub fn count_xmas(input: &str, xs: bool) -> usize {
+    count_xmas_in(&parse_input(input), xs)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Grid<char>) -> Option<Answer> {
+        Some(count_xmas_in(input, false).into())
+    }
+
+    fn part2(input: &Grid<char>) -> Option<Answer> {
+        Some(count_xmas_in(input, true).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d04::count_xmas;
@@ -11,6 +11,11 @@ if that becomes needed.
 */
 use std::collections::{HashMap, HashSet};
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 #[derive(Debug, Clone)]
 pub struct Rule {
     pub left: u32,
@@ -110,32 +115,37 @@ pub fn fix_ordering(constraints: &[Rule], ordering: &[u32]) -> Vec<u32> {
 }
 
 pub fn parse_input(input: &str) -> (Vec<Rule>, Vec<Vec<u32>>) {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<(Vec<Rule>, Vec<Vec<u32>>), ParseError> {
+    let source = Source::new(5, input);
     let mut lines = input.lines();
     let mut constraints = Vec::new();
     let mut orderings = Vec::new();
 
     loop {
-        let line = lines.next().expect("Unexpected EOF");
+        let line = source.next(&mut lines, source.end(), "a blank line after the rules")?;
         if line.is_empty() {
             break;
         }
-        let (left, right) = line.split_once("|").expect("Invalid rule");
+        let (left, right) = source.split_once(line, "|")?;
 
         constraints.push(Rule {
-            left: left.parse().expect("Invalid left"),
-            right: right.parse().expect("Invalid right"),
+            left: source.number(left)?,
+            right: source.number(right)?,
         })
     }
 
     for line in lines {
         orderings.push(
             line.split(",")
-                .map(|x| x.parse().expect("Invalid ordering"))
-                .collect(),
+                .map(|x| source.number(x))
+                .collect::<Result<_, _>>()?,
         );
     }
 
-    (constraints, orderings)
+    Ok((constraints, orderings))
 }
 
 fn midpoint(ordering: &[u32]) -> u32 {
@@ -150,17 +160,39 @@ pub fn sum_valid_middle(constraints: &[Rule], orderings: &[Vec<u32>]) -> u32 {
         .sum()
 }
 
+pub fn sum_fixed_middle(constraints: &[Rule], orderings: &[Vec<u32>]) -> u32 {
+    let fixed: Vec<_> = orderings
+        .iter()
+        .filter(|ordering| !is_valid(constraints, ordering))
+        .map(|ordering| fix_ordering(constraints, ordering))
+        .collect();
+    sum_valid_middle(constraints, &fixed)
+}
+
 pub fn check(input: &str, fix_only: &bool) -> u32 {
     let (constraints, orderings) = parse_input(input);
     if !fix_only {
         sum_valid_middle(&constraints, &orderings)
     } else {
-        let fixed: Vec<_> = orderings
-            .iter()
-            .filter(|ordering| !is_valid(&constraints, ordering))
-            .map(|ordering| fix_ordering(&constraints, ordering))
-            .collect();
-        sum_valid_middle(&constraints, &fixed)
+        sum_fixed_middle(&constraints, &orderings)
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (Vec<Rule>, Vec<Vec<u32>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1((constraints, orderings): &Self::Input) -> Option<Answer> {
+        Some(sum_valid_middle(constraints, orderings).into())
+    }
+
+    fn part2((constraints, orderings): &Self::Input) -> Option<Answer> {
+        Some(sum_fixed_middle(constraints, orderings).into())
     }
 }
 
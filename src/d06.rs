@@ -8,18 +8,22 @@ Basic idea:
     - 2-d array of bools (indicating points visited)
     - current position
     - current direction
-    - location of obstacles
+    - location of obstacles, sorted along each row and column so the next one
+      in the guard's way is a binary search
+
+2. For part 2, try an extra obstacle on each cell of the guard's path, and
+   check whether it ever stops in the same place facing the same way twice.
 */
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-#[derive(PartialEq, Eq, Debug, Hash, Clone)]
-pub enum Direction {
-    N,
-    E,
-    S,
-    W,
-}
+use serde::Serialize;
+
+use crate::{
+    render::{self, Style},
+    solution::{Answer, Solution},
+    utils::{Direction, Grid, ParseError, Point, Source},
+};
 
 #[derive(PartialEq, Eq, Debug, Hash, Clone)]
 pub enum Guard {
@@ -27,55 +31,193 @@ pub enum Guard {
     Position(usize, usize),
 }
 
+#[derive(Clone)]
 pub struct State {
     pub visited: Vec<Vec<bool>>, // index with [row][col]
     pub position: Guard,
     pub direction: Direction,
-    pub obstacles: Vec<(usize, usize)>,
+    pub obstacles: Obstacles,
 }
 
-pub fn parse_input(input: &str) -> State {
-    let mut obstacles = Vec::new();
-    let lines = input.lines();
-    let mut m = 0;
-    let mut n = 0;
-    let mut direction = Direction::N;
-    let mut position = (0, 0);
-
-    for (i, line) in lines.enumerate() {
-        n = line.len();
-        m = i;
-        for (j, c) in line.chars().enumerate() {
-            match c {
-                '#' => obstacles.push((i, j)),
-                '^' => {
-                    direction = Direction::N;
-                    position = (i, j);
-                }
-                '>' => {
-                    direction = Direction::E;
-                    position = (i, j);
-                }
-                'v' => {
-                    direction = Direction::S;
-                    position = (i, j);
-                }
-                '<' => {
-                    direction = Direction::W;
-                    position = (i, j);
-                }
-                _ => {}
-            }
+/// One straight line of the guard's walk, both ends included.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Segment {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub direction: Direction,
+    /// What it turned at, or `None` if it walked off the map.
+    pub obstacle: Option<(usize, usize)>,
+}
+
+/// Why the guard stopped walking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Exit {
+    LeftMap,
+    Loop,
+}
+
+/// The guard's walk from `State::run_traced`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Trace {
+    pub segments: Vec<Segment>,
+    pub exit: Exit,
+    /// How often the guard passed through each cell facing N, E, S and W.
+    /// Index with [row][col][direction].
+    pub visits: Vec<Vec<[u32; 4]>>,
+}
+
+impl Trace {
+    /// Where `direction` goes in `visits`.
+    pub fn index(direction: Direction) -> usize {
+        Direction::CARDINAL
+            .iter()
+            .position(|&d| d == direction)
+            .unwrap_or_else(|| panic!("The guard can't face {direction:?}."))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize trace.")
+    }
+}
+
+/// Where the obstacles are, indexed by row and by column.
+#[derive(Clone, Debug, Default)]
+pub struct Obstacles {
+    /// The columns of the obstacles in each row, sorted.
+    by_row: HashMap<usize, Vec<usize>>,
+    /// The rows of the obstacles in each column, sorted.
+    by_col: HashMap<usize, Vec<usize>>,
+}
+
+impl Obstacles {
+    pub fn contains(&self, (row, col): (usize, usize)) -> bool {
+        self.by_row
+            .get(&row)
+            .is_some_and(|cols| cols.binary_search(&col).is_ok())
+    }
+
+    /// Returns whether it's new.
+    pub fn insert(&mut self, (row, col): (usize, usize)) -> bool {
+        let cols = self.by_row.entry(row).or_default();
+        let Err(i) = cols.binary_search(&col) else {
+            return false;
+        };
+        cols.insert(i, col);
+        let rows = self.by_col.entry(col).or_default();
+        let i = rows.partition_point(|&r| r < row);
+        rows.insert(i, row);
+        true
+    }
+
+    /// Returns whether it was there.
+    pub fn remove(&mut self, (row, col): (usize, usize)) -> bool {
+        let Some(cols) = self.by_row.get_mut(&row) else {
+            return false;
+        };
+        let Ok(i) = cols.binary_search(&col) else {
+            return false;
+        };
+        cols.remove(i);
+        let rows = self.by_col.get_mut(&col).expect("Indexed both ways.");
+        let i = rows.binary_search(&row).expect("Indexed both ways.");
+        rows.remove(i);
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_row.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Row by row, left to right.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut rows: Vec<_> = self.by_row.keys().copied().collect();
+        rows.sort_unstable();
+        rows.into_iter()
+            .flat_map(|row| self.by_row[&row].iter().map(move |&col| (row, col)))
+    }
+
+    /// The first obstacle going `direction` from `position`, which has to be
+    /// up, down, left or right.
+    pub fn next(&self, (row, col): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        // The last one before `x`, or the first one after it.
+        let before = |sorted: &Vec<usize>, x| {
+            let i = sorted.partition_point(|&y| y < x);
+            i.checked_sub(1).map(|i| sorted[i])
+        };
+        let after = |sorted: &Vec<usize>, x| {
+            let i = sorted.partition_point(|&y| y <= x);
+            sorted.get(i).copied()
+        };
+        match direction {
+            Direction::N => before(self.by_col.get(&col)?, row).map(|r| (r, col)),
+            Direction::S => after(self.by_col.get(&col)?, row).map(|r| (r, col)),
+            Direction::W => before(self.by_row.get(&row)?, col).map(|c| (row, c)),
+            Direction::E => after(self.by_row.get(&row)?, col).map(|c| (row, c)),
+            _ => panic!("The guard can't move {direction:?}."),
         }
     }
+}
+
+impl FromIterator<(usize, usize)> for Obstacles {
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Obstacles {
+        let mut obstacles = Obstacles::default();
+        for p in iter {
+            obstacles.insert(p);
+        }
+        obstacles
+    }
+}
+
+pub fn parse_input(input: &str) -> State {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
-    let mut visited = vec![vec![false; n]; m + 1];
+pub fn try_parse_input(input: &str) -> Result<State, ParseError> {
+    let source = Source::new(6, input);
+    let guards = ['^', '>', 'v', '<'];
+    let (grid, marks) = Grid::parse(&source, input, &guards, |c| match c {
+        '#' => Some(true),
+        '.' | '^' | '>' | 'v' | '<' => Some(false),
+        _ => None,
+    })?;
+
+    let mut found = guards.iter().flat_map(|&c| {
+        let direction = Direction::from_arrow(c).expect("Guards are arrows.");
+        marks.all(c).iter().map(move |&p| (p, direction))
+    });
+    let (position, direction) = found
+        .next()
+        .ok_or_else(|| source.error(source.end(), "expected a guard (^, >, v or <)"))?;
+    if found.next().is_some() {
+        return Err(source.error(source.end(), "expected a single guard"));
+    }
+    let obstacles = grid
+        .iter()
+        .filter(|(_, &obstacle)| obstacle)
+        .map(|(p, _)| p)
+        .collect();
+
+    let mut visited = vec![vec![false; grid.n_cols()]; grid.n_rows()];
     visited[position.0][position.1] = true;
-    State {
+    Ok(State {
         visited,
         position: Guard::Position(position.0, position.1),
         direction,
         obstacles,
+    })
+}
+
+/// For `State::grid`.
+pub fn style(c: &char) -> Style {
+    match c {
+        '#' => Style::new('#', render::WHITE),
+        'X' => Style::new('X', render::YELLOW),
+        _ => Style::new(*c, render::GREY),
     }
 }
 
@@ -86,81 +228,39 @@ pub fn count_positions(input: &str) -> u32 {
     state.count_visited()
 }
 
+/// Where one more obstacle would trap the guard (part 2).
+pub fn count_loop_obstructions(input: &str) -> usize {
+    parse_input(input).loop_obstructions().len()
+}
+
+/// Whether the guard walks in circles. Only follows where it stops, so it
+/// doesn't mark anything as visited.
+fn loops(position: (usize, usize), direction: Direction, obstacles: &Obstacles) -> bool {
+    let mut seen = HashSet::new();
+    let mut guard = Guard::Position(position.0, position.1);
+    let mut direction = direction;
+    while let Guard::Position(row, col) = guard {
+        // Stopping in the same place facing the same way means it's been
+        // here before, and will be again.
+        if !seen.insert((row, col, direction)) {
+            return true;
+        }
+        guard = update_position(&(row, col), &direction, obstacles);
+        direction = direction.turn_right();
+    }
+    false
+}
+
 fn update_position(
     position: &(usize, usize),
     direction: &Direction,
-    obstacles: &[(usize, usize)],
+    obstacles: &Obstacles,
 ) -> Guard {
-    // When facing N/S, we'll keep our 'x' and move our 'y'.
-    // When facing W/E, vice-versa.
-    let (row, col) = position;
-    match direction {
-        // - When facing N, we want the *largest* 'row' s.t. row < current row
-        // TODO: fix all the other filters.
-        // TODO: Shift by 1 back in the direction we came from. You just hit the obstacle, not stand on it.
-        Direction::N => {
-            let candidates: Vec<_> = obstacles
-                .iter()
-                .filter(|(ob_row, ob_col)| ob_row < row && ob_col == col)
-                .collect();
-            // eprintln!("candidates: {candidates:?}");
-            if candidates.is_empty() {
-                Guard::Missing
-            } else {
-                let p = candidates.iter().max_by(|a, b| a.0.cmp(&b.0)).unwrap();
-                assert!(obstacles.contains(p));
-                Guard::Position(p.0 + 1, p.1)
-            }
-        }
-
-        // - When facing S, we want the *smallest* 'row' s.t. row > current row
-        Direction::S => {
-            let candidates: Vec<_> = obstacles
-                .iter()
-                .filter(|(ob_row, ob_col)| ob_row > row && ob_col == col)
-                .collect();
-            // eprintln!("candidates: {candidates:?}");
-            if candidates.is_empty() {
-                Guard::Missing
-            } else {
-                let p = candidates.iter().min_by(|a, b| a.0.cmp(&b.0)).unwrap();
-                assert!(obstacles.contains(p));
-                Guard::Position(p.0 - 1, p.1)
-            }
-        }
-
-        // - When facing W, we want the *largest* 'col' s.t. col < current col
-        Direction::W => {
-            let candidates: Vec<_> = obstacles
-                .iter()
-                .filter(|(ob_row, ob_col)| ob_row == row && ob_col < col)
-                .collect();
-            // eprintln!("candidates: {candidates:?}");
-            if candidates.is_empty() {
-                Guard::Missing
-            } else {
-                let p = candidates.iter().max_by(|a, b| a.0.cmp(&b.0)).unwrap();
-                assert!(obstacles.contains(p));
-                Guard::Position(p.0, p.1 + 1)
-            }
-        }
-
-        // - When facing E, we want the *smallest* 'col' s.t. col > current col
-        Direction::E => {
-            let candidates: Vec<_> = obstacles
-                .iter()
-                .filter(|(ob_row, ob_col)| ob_row == row && ob_col > col)
-                .collect();
-            // eprintln!("candidates: {candidates:?}");
-            if candidates.is_empty() {
-                Guard::Missing
-            } else {
-                let p = candidates.iter().min_by(|a, b| a.0.cmp(&b.0)).unwrap();
-                assert!(obstacles.contains(p));
-                Guard::Position(p.0, p.1 - 1)
-            }
-        }
-    }
+    // The nearest obstacle straight ahead; we stop on the cell just before it.
+    obstacles
+        .next(*position, *direction)
+        .and_then(|o| Point::from(o).step(direction.opposite()))
+        .map_or(Guard::Missing, |p| Guard::Position(p.row, p.col))
 }
 
 impl Guard {
@@ -180,143 +280,166 @@ impl State {
         self.visited[0].len()
     }
 
-    pub fn format_visited(&self) -> Vec<u8> {
-        let mut buf =
-            Vec::with_capacity(self.n_rows() * self.n_cols() + self.n_rows() + self.n_cols());
-
-        for row in self.visited.iter() {
-            for col in row.iter() {
-                if *col {
-                    buf.push(b'X');
-                } else {
-                    buf.push(b'.');
-                }
-            }
-            buf.push(b'\n');
+    /// What's been visited ('X'), and the obstacles ('#').
+    pub fn grid(&self) -> Grid<char> {
+        let rows = self
+            .visited
+            .iter()
+            .map(|row| row.iter().map(|&v| if v { 'X' } else { '.' }).collect())
+            .collect();
+        let mut grid = Grid::from_rows(rows);
+        for obstacle in self.obstacles.iter() {
+            grid[Point::from(obstacle)] = '#';
         }
+        grid
+    }
+
+    pub fn format_visited(&self) -> Vec<u8> {
+        render::text(&self.grid(), style).into_bytes()
+    }
 
-        // 0, 11, 22, 33
-        // row * 10 + row
-        for (row, col) in self.obstacles.iter() {
-            let idx = row * self.n_cols() + col + row;
-            buf[idx] = b'#'
+    pub fn run(&mut self) {
+        self.run_with(|_| {});
+    }
+
+    /// Like `run`, calling `on_step` with the starting state and again each
+    /// time the guard stops (at an obstacle or off the map).
+    pub fn run_with(&mut self, on_step: impl FnMut(&State)) {
+        if self.walk(on_step, None) == Exit::Loop {
+            panic!(
+                "Cycle detected! Direction: {:?}, Position: {:?}",
+                self.direction, self.position
+            );
         }
+    }
 
-        buf
+    /// Like `run`, keeping track of each straight line the guard walks and
+    /// how it ends, rather than panicking if it loops.
+    pub fn run_traced(&mut self) -> Trace {
+        let mut trace = Trace {
+            segments: Vec::new(),
+            exit: Exit::LeftMap,
+            visits: vec![vec![[0; 4]; self.n_cols()]; self.n_rows()],
+        };
+        trace.exit = self.walk(|_| {}, Some(&mut trace));
+        trace
     }
 
-    pub fn run(&mut self) {
+    fn walk(&mut self, mut on_step: impl FnMut(&State), mut trace: Option<&mut Trace>) -> Exit {
         // Figure out the positions we visit, based on
         // 1. our current position
         // 2. the direction we're facing
         // 3. the next obstacle we hit
 
-        // cleanup: generate the indices (based on direction & position) then set once
-
         let mut history = HashSet::new();
 
         loop {
-            if history.contains(&(self.direction.clone(), self.position.clone())) {
-                eprintln!("Cycle detected!");
-                eprintln!(
-                    "Direction: {:?}, Position: {:?}",
-                    self.direction, self.position
-                );
-                panic!("Cycle detected!")
+            if history.contains(&(self.direction, self.position.clone())) {
+                return Exit::Loop;
             }
 
-            history.insert((self.direction.clone(), self.position.clone()));
+            history.insert((self.direction, self.position.clone()));
+            on_step(self);
             match self.position {
-                Guard::Missing => break,
+                Guard::Missing => return Exit::LeftMap,
                 Guard::Position(row, col) => {
                     let new_spot = update_position(&(row, col), &self.direction, &self.obstacles);
-                    // eprintln!(
-                    //     "Move {:?} from {:?} -> {:?}",
-                    //     self.direction, self.position, new_spot
-                    // );
-
-                    match new_spot {
-                        Guard::Missing => {
-                            // 1. visited
-                            // We've exited the space. Fill zeros from the previous position to the edge in direction.
-                            match self.direction {
-                                Direction::N => {
-                                    // We're currently at (row, col)
-                                    for j in 0..=row {
-                                        self.visited[j][col] = true
-                                    }
-                                }
-
-                                Direction::S => {
-                                    // column is fixed, fill down
-                                    for j in row..self.n_rows() {
-                                        self.visited[j][col] = true
-                                    }
-                                }
-
-                                Direction::W => {
-                                    for i in 0..=col {
-                                        self.visited[row][i] = true
-                                    }
-                                }
-
-                                Direction::E => {
-                                    for i in col..self.n_cols() {
-                                        self.visited[row][i] = true
-                                    }
-                                }
-                            }
-
-                            // 2. position
-                            self.position = new_spot;
+
+                    // Everything from here up to where we stop is visited. If
+                    // there's nowhere to stop, that's everything up to the edge.
+                    let stop = new_spot.position().map(Point::from);
+                    let mut next = Some(Point::new(row, col));
+                    let mut end = (row, col);
+                    while let Some(p) =
+                        next.filter(|p| p.row < self.n_rows() && p.col < self.n_cols())
+                    {
+                        self.visited[p.row][p.col] = true;
+                        if let Some(trace) = trace.as_deref_mut() {
+                            trace.visits[p.row][p.col][Trace::index(self.direction)] += 1;
                         }
-                        Guard::Position(new_row, new_col) => {
-                            // update our state
-                            // 1. visited
-                            match self.direction {
-                                Direction::N => {
-                                    for j in new_row..row {
-                                        self.visited[j][col] = true
-                                    }
-                                    self.direction = Direction::E;
-                                }
-                                Direction::S => {
-                                    for j in row..new_row + 1 {
-                                        self.visited[j][col] = true
-                                    }
-                                    self.direction = Direction::W;
-                                }
-                                Direction::W => {
-                                    for i in new_col..col {
-                                        self.visited[row][i] = true
-                                    }
-                                    self.direction = Direction::N;
-                                }
-                                Direction::E => {
-                                    for i in col..new_col + 1 {
-                                        self.visited[row][i] = true
-                                    }
-                                    self.direction = Direction::S
-                                }
-                            }
-
-                            // 2. position
-                            self.position = new_spot;
+                        end = p.into();
+                        if Some(p) == stop {
+                            break;
                         }
+                        next = p.step(self.direction);
                     }
+
+                    if let Some(trace) = trace.as_deref_mut() {
+                        trace.segments.push(Segment {
+                            start: (row, col),
+                            end,
+                            direction: self.direction,
+                            obstacle: self.obstacles.next((row, col), self.direction),
+                        });
+                    }
+                    if stop.is_some() {
+                        self.direction = self.direction.turn_right();
+                    }
+                    self.position = new_spot;
                 }
             }
         }
     }
+
+    /// Every cell where one more obstacle traps the guard in a loop. Only the
+    /// cells on its path can change where it goes, and it's already standing
+    /// on the start.
+    pub fn loop_obstructions(&self) -> HashSet<(usize, usize)> {
+        let Guard::Position(row, col) = self.position else {
+            return HashSet::new();
+        };
+        let mut path = self.clone();
+        path.run();
+
+        let mut obstacles = self.obstacles.clone();
+        path.grid()
+            .iter()
+            .filter(|&(p, &c)| c == 'X' && p != (row, col))
+            .map(|(p, _)| p)
+            .filter(|&p| {
+                obstacles.insert(p);
+                let trapped = loops((row, col), self.direction, &obstacles);
+                obstacles.remove(p);
+                trapped
+            })
+            .collect()
+    }
+
     pub fn count_visited(&self) -> u32 {
         // eprintln!("Visited: {:#?}", self.visited);
         self.visited.iter().flatten().filter(|b| **b).count() as u32
     }
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = State;
+
+    fn parse(input: &str) -> Result<State, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &State) -> Option<Answer> {
+        let mut state = input.clone();
+        state.run();
+        Some(state.count_visited().into())
+    }
+
+    fn part2(input: &State) -> Option<Answer> {
+        Some(input.loop_obstructions().len().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::d06::{count_positions, parse_input, Direction, Guard};
+    use crate::{
+        d06::{
+            count_loop_obstructions, count_positions, parse_input, try_parse_input, Exit, Guard,
+            Obstacles, Segment, Trace,
+        },
+        utils::Direction,
+    };
 
     use super::update_position;
     const INPUT: &str = "\
@@ -348,7 +471,7 @@ mod tests {
         assert_eq!(state.direction, Direction::N);
         assert_eq!(state.position, Guard::Position(6, 4));
         assert_eq!(
-            state.obstacles,
+            state.obstacles.iter().collect::<Vec<_>>(),
             vec![
                 (0, 4),
                 (1, 9),
@@ -362,42 +485,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let error = try_parse_input("..#\n.^X\n...").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "X"));
+
+        let error = try_parse_input("..#\n.^\n...").err().unwrap();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (2, "expected 3 columns")
+        );
+
+        let error = try_parse_input("..#\n...").err().unwrap();
+        assert_eq!(error.message, "expected a guard (^, >, v or <)");
+    }
+
     #[test]
     fn test_part_1_example() {
         assert_eq!(count_positions(INPUT), 41);
     }
 
+    #[test]
+    fn test_part_2_example() {
+        assert_eq!(count_loop_obstructions(INPUT), 6);
+        let mut found: Vec<_> = parse_input(INPUT).loop_obstructions().into_iter().collect();
+        found.sort();
+        assert_eq!(found, [(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let trace = parse_input(INPUT).run_traced();
+        assert_eq!(trace.exit, Exit::LeftMap);
+        assert_eq!(
+            trace.segments[0],
+            Segment {
+                start: (6, 4),
+                end: (1, 4),
+                direction: Direction::N,
+                obstacle: Some((0, 4)),
+            }
+        );
+        let last = trace.segments.last().unwrap();
+        assert_eq!((last.end, last.obstacle), ((9, 7), None));
+
+        // Every visited cell, some more than once.
+        let counts = trace.visits.iter().flatten().map(|v| v.iter().sum::<u32>());
+        assert_eq!(counts.clone().filter(|&n| n > 0).count(), 41);
+        assert_eq!(trace.visits[6][4][Trace::index(Direction::N)], 1);
+        assert_eq!(trace.visits[6][4][Trace::index(Direction::W)], 1);
+
+        let json = trace.to_json();
+        assert!(json.starts_with(r#"{"segments":[{"start":[6,4],"end":[1,4],"direction":"N","#));
+        assert!(json.contains(r#""exit":"left_map""#));
+
+        // Boxed in, with nowhere to go.
+        let trace = parse_input(".#.\n#^#\n.#.").run_traced();
+        assert_eq!(trace.exit, Exit::Loop);
+        assert_eq!(trace.segments.len(), 4);
+    }
+
+    fn obstacles(list: &[(usize, usize)]) -> Obstacles {
+        list.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_obstacles() {
+        let mut obstacles = parse_input(INPUT).obstacles;
+        assert_eq!(obstacles.len(), 8);
+        assert_eq!(obstacles.next((6, 4), Direction::N), Some((0, 4)));
+        assert_eq!(obstacles.next((1, 4), Direction::E), Some((1, 9)));
+        assert_eq!(obstacles.next((6, 4), Direction::W), Some((6, 1)));
+        assert_eq!(obstacles.next((6, 4), Direction::S), None);
+
+        assert!(obstacles.insert((8, 4)));
+        assert!(!obstacles.insert((8, 4)));
+        assert_eq!(obstacles.next((6, 4), Direction::S), Some((8, 4)));
+        assert!(obstacles.contains((8, 4)));
+        assert!(obstacles.remove((8, 4)));
+        assert!(!obstacles.remove((8, 4)));
+        assert_eq!(obstacles.next((6, 4), Direction::S), None);
+        assert_eq!(obstacles.len(), 8);
+    }
+
     #[test]
     fn test_update_position() {
-        let result = update_position(&(0, 0), &Direction::E, &[(0, 2), (0, 3)]);
+        let result = update_position(&(0, 0), &Direction::E, &obstacles(&[(0, 2), (0, 3)]));
         let expected = Guard::Position(0, 1);
         assert_eq!(result, expected);
 
-        let result = update_position(&(0, 10), &Direction::W, &[(0, 2), (0, 3)]);
+        let result = update_position(&(0, 10), &Direction::W, &obstacles(&[(0, 2), (0, 3)]));
         let expected = Guard::Position(0, 4);
         assert_eq!(result, expected);
 
-        let result = update_position(&(0, 0), &Direction::S, &[(2, 0), (3, 0)]);
+        let result = update_position(&(0, 0), &Direction::S, &obstacles(&[(2, 0), (3, 0)]));
         let expected = Guard::Position(1, 0);
         assert_eq!(result, expected);
 
-        let result = update_position(&(10, 0), &Direction::N, &[(2, 0), (3, 0)]);
+        let result = update_position(&(10, 0), &Direction::N, &obstacles(&[(2, 0), (3, 0)]));
         let expected = Guard::Position(4, 0);
         assert_eq!(result, expected);
 
-        let result = update_position(&(0, 4), &Direction::E, &[(0, 2), (0, 3)]);
+        let result = update_position(&(0, 4), &Direction::E, &obstacles(&[(0, 2), (0, 3)]));
         let expected = Guard::Missing;
         assert_eq!(result, expected);
 
-        let result = update_position(&(0, 1), &Direction::W, &[(0, 2), (0, 3)]);
+        let result = update_position(&(0, 1), &Direction::W, &obstacles(&[(0, 2), (0, 3)]));
         let expected = Guard::Missing;
         assert_eq!(result, expected);
 
-        let result = update_position(&(4, 0), &Direction::S, &[(2, 0), (3, 0)]);
+        let result = update_position(&(4, 0), &Direction::S, &obstacles(&[(2, 0), (3, 0)]));
         let expected = Guard::Missing;
         assert_eq!(result, expected);
 
-        let result = update_position(&(1, 0), &Direction::N, &[(2, 0), (3, 0)]);
+        let result = update_position(&(1, 0), &Direction::N, &obstacles(&[(2, 0), (3, 0)]));
         let expected = Guard::Missing;
         assert_eq!(result, expected);
     }
@@ -1,6 +1,9 @@
-use std::vec;
+use itertools::Itertools;
 
-use itertools::{self, Itertools};
+use crate::{
+    solution::{Answer, Part, Solution},
+    utils::{ParseError, Source},
+};
 
 #[derive(PartialEq, Eq, Debug)]
 pub struct Line {
@@ -8,91 +11,333 @@ pub struct Line {
     pub operands: Vec<u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum Operator {
     Add,
+    Sub,
     Mul,
+    /// Integer division, rounding down.
+    Div,
+    Pow,
     Concat,
 }
 
+impl Operator {
+    /// `lhs` then `rhs`, or `None` if it overflows (or goes below 0, or
+    /// divides by 0).
+    pub fn apply(self, lhs: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Sub => lhs.checked_sub(rhs),
+            Operator::Mul => lhs.checked_mul(rhs),
+            Operator::Div => lhs.checked_div(rhs),
+            Operator::Pow => lhs.checked_pow(u32::try_from(rhs).ok()?),
+            Operator::Concat => lhs.checked_mul(shift(rhs)?)?.checked_add(rhs),
+        }
+    }
+
+    /// Whether `undo` works: there's at most one `lhs` for a result.
+    pub fn is_invertible(self) -> bool {
+        !matches!(self, Operator::Div | Operator::Pow)
+    }
+
+    /// The `lhs` that `apply` would turn into `result` with `rhs`, if there
+    /// is one. `None` for `Mul` by 0 too, where any `lhs` would do.
+    pub fn undo(self, result: u64, rhs: u64) -> Option<u64> {
+        match self {
+            Operator::Add => result.checked_sub(rhs),
+            Operator::Sub => result.checked_add(rhs),
+            Operator::Mul => (rhs != 0 && result.is_multiple_of(rhs)).then(|| result / rhs),
+            Operator::Concat => {
+                let shift = shift(rhs)?;
+                (result % shift == rhs).then(|| result / shift)
+            }
+            Operator::Div | Operator::Pow => panic!("{self:?} can't be undone."),
+        }
+    }
+
+    /// With `Rules::precedence`, higher goes first.
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Add | Operator::Sub => 0,
+            Operator::Mul | Operator::Div => 1,
+            Operator::Pow => 2,
+            Operator::Concat => 3,
+        }
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::Pow => "^",
+            Operator::Concat => "||",
+        }
+    }
+}
+
+/// Which operators can go between the operands, and how to evaluate them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub operators: Vec<Operator>,
+    /// Evaluate `||`, then `^` (right to left), then `*` and `/`, then `+`
+    /// and `-`, rather than strictly left to right as the puzzle does.
+    pub precedence: bool,
+}
+
+impl Rules {
+    pub fn new(operators: Vec<Operator>, precedence: bool) -> Rules {
+        Rules {
+            operators,
+            precedence,
+        }
+    }
+
+    /// Part 1 only has `Add` and `Mul`; part 2 adds `Concat`.
+    pub fn for_part(part: Part) -> Rules {
+        let operators = match part {
+            Part::One => vec![Operator::Add, Operator::Mul],
+            Part::Two => vec![Operator::Add, Operator::Mul, Operator::Concat],
+        };
+        Rules::new(operators, false)
+    }
+}
+
+/// What to multiply by to make room for `n`'s digits, or `None` if that
+/// doesn't fit in a u64 (`n` is 10^19 or more).
+fn shift(n: u64) -> Option<u64> {
+    10u64.checked_pow(n.checked_ilog10().unwrap_or(0) + 1)
+}
+
 pub fn parse_input(input: &str) -> Vec<Line> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Line>, ParseError> {
+    let source = Source::new(7, input);
     let mut lines = Vec::new();
 
     for line in input.lines() {
-        let (result, operands_raw) = line.split_once(": ").expect("Missing ':'");
+        let (result, operands_raw) = source.split_once(line, ": ")?;
         // eprintln!("result={result}");
         lines.push(Line {
-            result: result.parse().unwrap(),
+            result: source.number(result)?,
             operands: operands_raw
                 .split(" ")
-                .map(|x| x.parse().unwrap())
-                .collect(),
+                .map(|x| source.number(x))
+                .collect::<Result<_, _>>()?,
         });
     }
-    lines
+    Ok(lines)
 }
 
 impl Line {
-    pub fn find_solutions(&self) -> bool {
-        // let mut result = Vec::new();
-        let n_operators = self.operands.len() - 1;
-        let options = [Operator::Add, Operator::Mul, Operator::Concat];
-
-        // [ [Add], [Mul] ]
-        // [ [Add, Add], [Add, Mul], [Mul, Add], [Mul, Mul]]
-        // [ [Add, Add, Add], [Add, Add, Mul], [Add, Mul, Mul], ... [Mul, Mul, Mul]]
-        // ...
-
-        for operators in vec![&options; n_operators]
-            .iter()
-            .map(|x| x.iter())
-            .multi_cartesian_product()
-        {
-            let mut acc = 0;
-            acc += self.operands[0];
-
-            for (operand, operator) in self.operands[1..].iter().zip(&operators) {
-                match operator {
-                    Operator::Add => acc += operand,
-                    Operator::Mul => acc *= operand,
-                    Operator::Concat => {
-                        let new = (acc.to_string() + &operand.to_string()).parse().unwrap();
-                        acc = new;
+    pub fn find_solutions(&self, rules: &Rules) -> bool {
+        self.solve(rules).is_some()
+    }
+
+    /// Operators, left to right, that make the operands come to `result`.
+    pub fn solve(&self, rules: &Rules) -> Option<Vec<Operator>> {
+        let options = &rules.operators;
+        let mut operators = Vec::with_capacity(self.operands.len());
+        if rules.precedence {
+            // No shortcuts: try everything.
+            return vec![options; self.operands.len().saturating_sub(1)]
+                .iter()
+                .map(|x| x.iter().copied())
+                .multi_cartesian_product()
+                .find(|operators| self.evaluate(operators, true) == Some(self.result));
+        }
+        // Multiplying by 0 can't be undone either: it loses the left side.
+        let has_zero = self.operands.iter().skip(1).any(|&o| o == 0);
+        if options.iter().all(|o| o.is_invertible()) && !has_zero {
+            if solve_backwards(self.result, &self.operands, options, &mut operators) {
+                operators.reverse();
+                return Some(operators);
+            }
+        } else {
+            let (&first, rest) = self.operands.split_first()?;
+            if solve_forwards(first, self.result, rest, options, &mut operators) {
+                return Some(operators);
+            }
+        }
+        None
+    }
+
+    /// Left to right, or with `precedence` as in `Rules`. `None` if anything
+    /// overflows.
+    pub fn evaluate(&self, operators: &[Operator], precedence: bool) -> Option<u64> {
+        if !precedence {
+            let (first, rest) = self.operands.split_first()?;
+            return rest
+                .iter()
+                .zip(operators)
+                .try_fold(*first, |acc, (&operand, operator)| {
+                    operator.apply(acc, operand)
+                });
+        }
+
+        // Collapse the tightest binding operators into their operands first.
+        let mut values = self.operands.clone();
+        let mut operators = operators.to_vec();
+        for level in (0..=Operator::Concat.precedence()).rev() {
+            if level == Operator::Pow.precedence() {
+                for i in (0..operators.len()).rev() {
+                    if operators[i].precedence() == level {
+                        values[i] = operators.remove(i).apply(values[i], values.remove(i + 1))?;
                     }
                 }
-
-                if acc > self.result {
-                    break;
+            } else {
+                let mut i = 0;
+                while i < operators.len() {
+                    if operators[i].precedence() == level {
+                        values[i] = operators.remove(i).apply(values[i], values.remove(i + 1))?;
+                    } else {
+                        i += 1;
+                    }
                 }
             }
+        }
+        values.first().copied()
+    }
 
-            if acc == self.result {
-                // eprintln!("Valid! {:?}, permutation={:?}", self, operators);
-                return true;
-            }
+    /// Like `3267 = 81 * 40 + 27`.
+    pub fn expression(&self, operators: &[Operator]) -> String {
+        let mut buf = format!("{} = {}", self.result, self.operands[0]);
+        for (operand, operator) in self.operands[1..].iter().zip(operators) {
+            buf.push_str(&format!(" {} {operand}", operator.symbol()));
+        }
+        buf
+    }
+}
+
+/// Left to right, for operators that can't be undone. `operators` collects
+/// them in order.
+fn solve_forwards(
+    acc: u64,
+    result: u64,
+    operands: &[u64],
+    options: &[Operator],
+    operators: &mut Vec<Operator>,
+) -> bool {
+    let Some((&next, rest)) = operands.split_first() else {
+        return acc == result;
+    };
+    for &operator in options {
+        let Some(acc) = operator.apply(acc, next) else {
+            continue;
+        };
+        operators.push(operator);
+        if solve_forwards(acc, result, rest, options, operators) {
+            return true;
+        }
+        operators.pop();
+    }
+    false
+}
+
+/*
+Working backwards from the result prunes most of the search: the last
+operator has to be one that can be undone (`*` needs the result to divide
+evenly, `||` needs it to end in the last operand's digits), and what's left
+has to come from the other operands the same way.
+
+`operators` collects the operators last first.
+*/
+fn solve_backwards(
+    result: u64,
+    operands: &[u64],
+    options: &[Operator],
+    operators: &mut Vec<Operator>,
+) -> bool {
+    let Some((&last, rest)) = operands.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return result == last;
+    }
+    for &operator in options {
+        let Some(before) = operator.undo(result, last) else {
+            continue;
+        };
+        operators.push(operator);
+        if solve_backwards(before, rest, options, operators) {
+            return true;
         }
-        false
+        operators.pop();
     }
+    false
 }
 
-pub fn sum_valid_lines(lines: &[Line]) -> u64 {
+pub fn sum_valid_lines(lines: &[Line], rules: &Rules) -> u64 {
     lines
         .iter()
-        .filter(|line| line.find_solutions())
+        .filter(|line| line.find_solutions(rules))
         .map(|line| line.result)
         .sum()
 }
 
-pub fn total_calibration_result(input: &str) -> u64 {
+/// Like `sum_valid_lines`, checking lines on `threads` threads at once, or
+/// one per core if `None`.
+#[cfg(feature = "parallel")]
+pub fn sum_valid_lines_parallel(lines: &[Line], rules: &Rules, threads: Option<usize>) -> u64 {
+    use rayon::prelude::*;
+
+    let sum = || {
+        lines
+            .par_iter()
+            .filter(|line| line.find_solutions(rules))
+            .map(|line| line.result)
+            .sum()
+    };
+    match threads {
+        None => sum(),
+        Some(n) => rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()
+            .expect("Failed to start threads.")
+            .install(sum),
+    }
+}
+
+/// In parallel with the `parallel` feature, otherwise one line at a time.
+fn sum_lines(lines: &[Line], rules: &Rules) -> u64 {
+    #[cfg(feature = "parallel")]
+    return sum_valid_lines_parallel(lines, rules, None);
+    #[cfg(not(feature = "parallel"))]
+    sum_valid_lines(lines, rules)
+}
+
+pub fn total_calibration_result(input: &str, rules: &Rules) -> u64 {
     let lines = parse_input(input);
-    sum_valid_lines(&lines)
+    sum_valid_lines(&lines, rules)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Vec<Line>) -> Option<Answer> {
+        Some(sum_lines(input, &Rules::for_part(Part::One)).into())
+    }
+
+    fn part2(input: &Vec<Line>) -> Option<Answer> {
+        Some(sum_lines(input, &Rules::for_part(Part::Two)).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d07::Line;
+    use crate::{d07::Line, solution::Part};
 
-    use super::{parse_input, sum_valid_lines};
+    use super::{parse_input, sum_valid_lines, Operator, Rules};
 
     const INPUT: &str = "\
 190: 10 19
@@ -119,8 +364,109 @@ mod tests {
     #[test]
     fn test_example_1() {
         let data = parse_input(INPUT);
-        let result = sum_valid_lines(&data);
-        // assert_eq!(result, 3749);  // part 1
-        assert_eq!(result, 11387);
+        assert_eq!(sum_valid_lines(&data, &Rules::for_part(Part::One)), 3749);
+        assert_eq!(sum_valid_lines(&data, &Rules::for_part(Part::Two)), 11387);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel() {
+        let data = parse_input(INPUT);
+        for part in Part::ALL {
+            let rules = Rules::for_part(part);
+            let serial = sum_valid_lines(&data, &rules);
+            assert_eq!(super::sum_valid_lines_parallel(&data, &rules, None), serial);
+            assert_eq!(
+                super::sum_valid_lines_parallel(&data, &rules, Some(2)),
+                serial
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve() {
+        let data = parse_input(INPUT);
+        let (part_1, part_2) = (Rules::for_part(Part::One), Rules::for_part(Part::Two));
+        use Operator::*;
+        assert_eq!(data[0].solve(&part_1), Some(vec![Mul]));
+        assert_eq!(data[1].solve(&part_1).map(|ops| ops.len()), Some(2));
+        assert_eq!(data[8].solve(&part_1), Some(vec![Add, Mul, Add]));
+        assert_eq!(data[4].solve(&part_1), None);
+        assert_eq!(data[4].solve(&part_2), Some(vec![Mul, Concat, Mul]));
+        assert_eq!(
+            data[4].expression(&[Mul, Concat, Mul]),
+            "7290 = 6 * 8 || 6 * 15"
+        );
+
+        for line in data.iter() {
+            if let Some(operators) = line.solve(&part_2) {
+                assert_eq!(line.evaluate(&operators, false), Some(line.result));
+            }
+        }
+    }
+
+    #[test]
+    fn test_operators() {
+        assert_eq!(Operator::Concat.apply(12, 345), Some(12345));
+        assert_eq!(Operator::Concat.apply(12, 0), Some(120));
+        assert_eq!(Operator::Concat.undo(12345, 345), Some(12));
+        assert_eq!(Operator::Concat.undo(12345, 45), Some(123));
+        assert_eq!(Operator::Concat.undo(12345, 5), Some(1234));
+        assert_eq!(Operator::Concat.undo(12345, 44), None);
+        assert_eq!(Operator::Mul.undo(12, 5), None);
+        assert_eq!(Operator::Mul.undo(0, 0), None);
+        assert_eq!(Operator::Add.undo(3, 5), None);
+        assert_eq!(Operator::Mul.apply(u64::MAX, 2), None);
+        assert_eq!(Operator::Concat.apply(0, 10u64.pow(19)), None);
+        assert_eq!(Operator::Concat.undo(u64::MAX, 10u64.pow(19)), None);
+    }
+
+    #[test]
+    fn test_zero_operand() {
+        use Operator::*;
+        let rules = Rules::for_part(Part::One);
+        let line = parse_input("0: 5 0").remove(0);
+        assert_eq!(line.solve(&rules), Some(vec![Mul]));
+        let line = parse_input("0: 5 0 3").remove(0);
+        assert_eq!(line.solve(&rules), Some(vec![Mul, Mul]));
+        let line = parse_input("8: 5 0 3").remove(0);
+        assert_eq!(line.solve(&rules), Some(vec![Add, Add]));
+    }
+
+    #[test]
+    fn test_rules() {
+        use Operator::*;
+        let line = |result, operands: &[u64]| Line {
+            result,
+            operands: operands.to_vec(),
+        };
+
+        // Backwards, through subtraction.
+        let rules = Rules::new(vec![Add, Sub], false);
+        assert_eq!(line(5, &[10, 2, 3]).solve(&rules), Some(vec![Sub, Sub]));
+        assert_eq!(line(20, &[10, 2, 3]).solve(&rules), None);
+
+        // Forwards, since division can't be undone.
+        let rules = Rules::new(vec![Sub, Div], false);
+        assert_eq!(line(2, &[10, 2, 3]).solve(&rules), Some(vec![Sub, Div]));
+
+        // 1 + 2 * 3 is only 7 with precedence.
+        let sum = line(7, &[1, 2, 3]);
+        assert_eq!(sum.solve(&Rules::for_part(Part::One)), None);
+        let rules = Rules::new(vec![Add, Mul], true);
+        assert_eq!(sum.solve(&rules), Some(vec![Add, Mul]));
+        assert_eq!(sum.evaluate(&[Add, Mul], false), Some(9));
+
+        let powers = line(0, &[2, 3, 2]);
+        assert_eq!(powers.evaluate(&[Pow, Pow], true), Some(512));
+        assert_eq!(powers.evaluate(&[Pow, Pow], false), Some(64));
+        assert_eq!(line(0, &[7, 2]).evaluate(&[Div], false), Some(3));
+        assert_eq!(line(0, &[1, 2, 3]).evaluate(&[Sub, Add], false), None);
+        assert_eq!(
+            line(0, &[2, 1, 2, 3]).evaluate(&[Mul, Concat, Add], true),
+            Some(27)
+        );
+        assert_eq!(line(0, &[10, 0]).evaluate(&[Div], true), None);
+        assert_eq!(line(0, &[10, 100]).evaluate(&[Pow], true), None);
     }
 }
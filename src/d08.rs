@@ -2,6 +2,12 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::{
+    render::{self, Style},
+    solution::{Answer, Solution},
+    utils::{self, ParseError, Point, Source},
+};
+
 /*
 
 Decision: how to represent the grid of antenna and antinodes?
@@ -21,81 +27,66 @@ pub struct Grid {
 }
 
 pub fn parse_input(input: &str) -> Grid {
-    let mut antennae: Locations = HashMap::new();
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
-    for (i, line) in input.lines().enumerate() {
-        for (j, char) in line.chars().enumerate() {
-            if char != '.' {
-                antennae.entry(char).or_default().push((i, j));
-            }
+pub fn try_parse_input(input: &str) -> Result<Grid, ParseError> {
+    let (grid, _) = utils::Grid::parse(&Source::new(8, input), input, &[], Some)?;
+
+    let mut antennae: Locations = HashMap::new();
+    for (position, &char) in grid.iter() {
+        if char != '.' {
+            antennae.entry(char).or_default().push(position);
         }
     }
 
-    Grid {
+    Ok(Grid {
         antennae,
-        n_rows: input.lines().count(),
-        n_cols: input.lines().next().unwrap().len(),
+        n_rows: grid.n_rows(),
+        n_cols: grid.n_cols(),
+    })
+}
+
+impl Grid {
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.n_rows && p.col < self.n_cols
     }
 }
 
-pub fn find_antinodes(grid: &Grid) -> HashSet<(usize, usize)> {
+/// Part 1: only the two antinodes on either side of each pair of antennae.
+pub fn find_direct_antinodes(grid: &Grid) -> HashSet<(usize, usize)> {
     let mut locations = HashSet::new();
-    // = (1, 8) + (3, 7) = (11, 3)
-    // Found SW antinode! char='0' a=(1, 8) b=(3, 7) p=(5, 6) delta=(-2, 1) abs_delta=(2, 1)
-    // Found SW antinode! char='0' a=(1, 8) b=(3, 7) p=(7, 4) delta=(-2, 1) abs_delta=(2, 1)
-    // Found SW antinode! char='0' a=(1, 8) b=(3, 7) p=(9, 2) delta=(-2, 1) abs_delta=(2, 1)
-    // Found SW antinode! char='0' a=(1, 8) b=(3, 7) p=(11, 0) delta=(-2, 1) abs_delta=(2, 1)
 
-    for (_char, positions) in grid.antennae.iter() {
+    for positions in grid.antennae.values() {
         for combination in positions.iter().combinations(2) {
-            // In part 2, each antenna is also an antinode, if there's more than one of them at
-            // that frequency.
-            let (a, b) = (combination[0], combination[1]);
-            locations.insert((a.0, a.1));
-            locations.insert((b.0, b.1));
-            let delta = ((a.0 as i32 - b.0 as i32), (a.1 as i32 - b.1 as i32));
-
-            let abs_delta: (i32, i32) = (delta.0.abs(), delta.1.abs());
-
-            if delta.0 <= 0 && delta.1 <= 0 {
-                // Move NW from 'a' and SE from 'b'
-                let mut c_a = (a.0 as i32 - abs_delta.0, a.1 as i32 - abs_delta.1);
-                let mut c_b = (b.0 as i32 + abs_delta.0, b.1 as i32 + abs_delta.1);
-
-                while c_a.0 >= 0 && c_a.1 >= 0 {
-                    // eprintln!("Found NW antinode! char={_char:?} a={a:?} b={b:?} p={c_a:?} delta={delta:?} abs_delta={abs_delta:?}");
-                    locations.insert((c_a.0 as usize, c_a.1 as usize));
-                    c_a = (c_a.0 - abs_delta.0, c_a.1 - abs_delta.1);
+            let (a, b) = (Point::from(*combination[0]), Point::from(*combination[1]));
+            for (from, to) in [(a, b), (b, a)] {
+                // `to` reflected through `from`.
+                if let Some(p) = from.checked_add(from - to).filter(|&p| grid.contains(p)) {
+                    locations.insert(p.into());
                 }
+            }
+        }
+    }
 
-                while (c_b.0 as usize) < grid.n_rows && (c_b.1 as usize) < grid.n_cols {
-                    // eprintln!("Found SE antinode! char={_char:?} a={a:?} b={b:?} p={c_b:?} delta={delta:?} abs_delta={abs_delta:?}");
-                    locations.insert((c_b.0 as usize, c_b.1 as usize));
-                    c_b = (c_b.0 + abs_delta.0, c_b.1 + abs_delta.1);
-                }
-            } else if delta.0 <= 0 && delta.1 > 0 {
-                // Move NE from 'a' and SW from 'b'
-                let mut c_a = (a.0 as i32 - abs_delta.0, a.1 as i32 + abs_delta.1);
-                let mut c_b = (b.0 as i32 + abs_delta.0, b.1 as i32 - abs_delta.1);
-
-                while c_a.0 >= 0 && (c_a.1 as usize) < grid.n_cols {
-                    // eprintln!("Found NE antinode! char={_char:?} a={a:?} b={b:?} p={c_a:?} delta={delta:?} abs_delta={abs_delta:?}");
-                    locations.insert((c_a.0 as usize, c_a.1 as usize));
-                    c_a = (c_a.0 - abs_delta.0, c_a.1 + abs_delta.1);
-                }
+    locations
+}
 
-                while (c_b.0 as usize) < grid.n_rows && c_b.1 >= 0 {
-                    // eprintln!("Found SW antinode! char={_char:?} a={a:?} b={b:?} p={c_b:?} delta={delta:?} abs_delta={abs_delta:?}");
-                    locations.insert((c_b.0 as usize, c_b.1 as usize));
-                    c_b = (c_b.0 + abs_delta.0, c_b.1 - abs_delta.1);
+pub fn find_antinodes(grid: &Grid) -> HashSet<(usize, usize)> {
+    let mut locations = HashSet::new();
+
+    for positions in grid.antennae.values() {
+        for combination in positions.iter().combinations(2) {
+            // In part 2, each antenna is also an antinode, if there's more than one of them at
+            // that frequency. So walk from each antenna away from the other, including itself.
+            let (a, b) = (Point::from(*combination[0]), Point::from(*combination[1]));
+            for (from, to) in [(a, b), (b, a)] {
+                let delta = from - to;
+                let mut p = Some(from);
+                while let Some(antinode) = p.filter(|&p| grid.contains(p)) {
+                    locations.insert(antinode.into());
+                    p = antinode.checked_add(delta);
                 }
-            } else if delta.0 > 0 && delta.1 <= 0 {
-                // our parse order means we don't hit this from the CLI.
-                todo!()
-            } else {
-                // delta.0 > 0 && delta.1 > 0
-                // our parse order means we don't hit this from the CLI.
-                todo!()
             }
         }
     }
@@ -103,27 +94,31 @@ pub fn find_antinodes(grid: &Grid) -> HashSet<(usize, usize)> {
     locations
 }
 
-pub fn format_antinodes(grid: &Grid, antinodes: &HashSet<(usize, usize)>) -> String {
-    // let mut buf = Vec::with_capacity(grid.n_cols * grid.n_rows + grid.n_rows);
-    let mut buf = vec![&'.'; grid.n_cols * grid.n_rows + grid.n_rows];
-
-    // in general, the location (i, j) is at (i * n_cols + j + i)
+/// The antennae, with the antinodes over them as '#'.
+pub fn antinode_grid(grid: &Grid, antinodes: &HashSet<(usize, usize)>) -> utils::Grid<char> {
+    let mut result = utils::Grid::filled(grid.n_rows, grid.n_cols, '.');
     for (char, locations) in grid.antennae.iter() {
-        for (i, j) in locations.iter() {
-            buf[i * grid.n_cols + j + i] = char
+        for &location in locations.iter() {
+            result[Point::from(location)] = *char;
         }
     }
+    for &antinode in antinodes.iter() {
+        result[Point::from(antinode)] = '#';
+    }
+    result
+}
 
-    for row in 0..grid.n_rows {
-        for col in 0..grid.n_cols {
-            if antinodes.contains(&(row, col)) {
-                buf[row * grid.n_cols + col + row] = &'#'
-            }
-        }
-        buf[row * grid.n_cols + grid.n_cols + row] = &'\n'
+/// For `antinode_grid`.
+pub fn style(c: &char) -> Style {
+    match c {
+        '#' => Style::new('#', render::RED),
+        '.' => Style::new('.', render::GREY),
+        _ => Style::new(*c, render::BLUE),
     }
+}
 
-    buf.into_iter().collect()
+pub fn format_antinodes(grid: &Grid, antinodes: &HashSet<(usize, usize)>) -> String {
+    render::text(&antinode_grid(grid, antinodes), style)
 }
 
 pub fn count_antinodes(input: &str) -> usize {
@@ -136,11 +131,29 @@ pub fn count_antinodes(input: &str) -> usize {
     antinodes.len()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Grid, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Grid) -> Option<Answer> {
+        Some(find_direct_antinodes(input).len().into())
+    }
+
+    fn part2(input: &Grid) -> Option<Answer> {
+        Some(find_antinodes(input).len().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d08::count_antinodes;
 
-    use super::parse_input;
+    use super::{find_direct_antinodes, parse_input};
 
     const INPUT: &str = "\
 ............
@@ -172,4 +185,10 @@ mod tests {
         let result = count_antinodes(INPUT);
         assert_eq!(result, 34);
     }
+
+    #[test]
+    fn test_part_1_example() {
+        let result = find_direct_antinodes(&parse_input(INPUT));
+        assert_eq!(result.len(), 14);
+    }
 }
@@ -19,6 +19,11 @@ I think we want a mapping from
 
 use std::collections::VecDeque;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 #[derive(Debug, Clone)]
 pub struct Block {
     pub id: usize,
@@ -203,6 +208,24 @@ pub fn run(input: &str) -> u64 {
     checksum
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        let input = input.trim_end();
+        match input.find(|c: char| !c.is_ascii_digit()) {
+            Some(i) => Err(Source::new(9, input).unexpected_char(input, i)),
+            None => Ok(input.to_string()),
+        }
+    }
+
+    fn part1(input: &String) -> Option<Answer> {
+        Some(run(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d09::run;
@@ -1,133 +1,60 @@
 // use itertools::Itertools;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{Grid, ParseError, Source},
+};
+
 use itertools::Itertools;
 
-pub struct Grid {
-    pub values: Vec<Vec<u8>>,
+pub fn parse_input(input: &str) -> Grid<u8> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
-impl Grid {
-    pub fn n_rows(&self) -> usize {
-        self.values.len()
-    }
-
-    pub fn n_cols(&self) -> usize {
-        self.values[0].len()
-    }
-
-    pub fn up(&self, row: usize, col: usize) -> Option<&u8> {
-        self.iloc(row - 1, col)
-    }
-
-    pub fn down(&self, row: usize, col: usize) -> Option<&u8> {
-        self.iloc(row + 1, col)
-    }
-
-    pub fn left(&self, row: usize, col: usize) -> Option<&u8> {
-        self.iloc(row, col - 1)
-    }
-    pub fn right(&self, row: usize, col: usize) -> Option<&u8> {
-        self.iloc(row, col + 1)
-    }
-
-    pub fn iloc(&self, row: usize, col: usize) -> Option<&u8> {
-        self.values.get(row)?.get(col)
-    }
-    //     eprintln!("row={row:} col={col}");
-    //     self.values.get(0).or_else(|r| r.get)
-    //     if (row >= self.n_rows()) || (col == 0 || col == self.n_cols() - 1) {
-    //         None
-    //     } else {
-    //         Some(self.values[row][col])
-    //     }
-    // }
-
-    pub fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
-        let choices = [
-            (row.checked_sub(1), Some(col)),
-            (row.checked_add(1), Some(col)),
-            (Some(row), col.checked_sub(1)),
-            (Some(row), col.checked_add(1)),
-        ];
-        choices
-            .iter()
-            .filter(|(r, c)| {
-                r.is_some()
-                    && c.is_some()
-                    && r.unwrap() < self.n_rows()
-                    && c.unwrap() < self.n_cols()
-            })
-            .map(|a| (a.0.unwrap(), a.1.unwrap()))
-            .collect()
-    }
+pub fn try_parse_input(input: &str) -> Result<Grid<u8>, ParseError> {
+    let source = Source::new(10, input);
+    let (grid, _) = Grid::parse(&source, input, &[], |c| c.to_digit(10).map(|d| d as u8))?;
+    Ok(grid)
 }
 
-pub fn parse_input(input: &str) -> Grid {
+pub fn find_trailheads_candidates(grid: &Grid<u8>) -> Vec<(usize, usize)> {
     let mut result = Vec::new();
-    for line in input.lines() {
-        let mut row = Vec::with_capacity(line.len());
-        for char in line.chars() {
-            row.push(char.to_digit(10).unwrap() as u8);
-        }
-        result.push(row);
-    }
-    Grid { values: result }
-}
 
-pub fn find_trailheads_candidates(grid: &Grid) -> Vec<(usize, usize)> {
-    let mut result = Vec::new();
-
-    for (i, row) in grid.values.iter().enumerate() {
-        for (j, val) in row.iter().enumerate() {
-            // must trailheads be on the edge?
-            // if i == 0 || i == m || j == 0 || j == n && *val == 0 {
-            if *val == 0 {
-                result.push((i, j));
-            }
+    for (position, val) in grid.iter() {
+        // must trailheads be on the edge?
+        // if i == 0 || i == m || j == 0 || j == n && *val == 0 {
+        if *val == 0 {
+            result.push(position);
         }
     }
 
     result
 }
 
-pub fn find_trails(grid: &Grid, row: &usize, col: &usize, value: &u8) -> Vec<(usize, usize)> {
+pub fn find_trails(grid: &Grid<u8>, row: &usize, col: &usize, value: &u8) -> Vec<(usize, usize)> {
     if *value == 9 {
         vec![(*row, *col)]
     } else {
         let neighbors = grid.neighbors(*row, *col);
         // eprintln!("row={row} col={col} value={value} neighbors={neighbors:?}");
         neighbors
-            .iter()
-            .filter(|(r, c)| grid.iloc(*r, *c).unwrap() == &(value + 1))
-            .flat_map(|(r, c)| find_trails(grid, r, c, &(value + 1)))
+            .filter(|&(r, c)| grid.iloc(r, c) == Some(&(value + 1)))
+            .flat_map(|(r, c)| find_trails(grid, &r, &c, &(value + 1)))
             .unique()
             .collect()
     }
 }
 
-pub fn find_trails2(
-    grid: &Grid,
-    row: &usize,
-    col: &usize,
-    value: &u8,
-    history: &[(usize, usize)],
-) -> Vec<(usize, usize)> {
-    let new_history = [history, &[(*row, *col)]].concat();
+/// The rating: how many distinct trails lead from here to any '9'. Unlike
+/// `find_trails`, two trails ending at the same '9' both count.
+pub fn count_trails(grid: &Grid<u8>, row: &usize, col: &usize, value: &u8) -> usize {
     if *value == 9 {
-        new_history
+        1
     } else {
-        let neighbors = grid.neighbors(*row, *col);
-        eprintln!("row={row} col={col} value={value} neighbors={neighbors:?} new_history=:{new_history:?}");
-        neighbors
-            .iter()
-            .filter(|(r, c)| grid.iloc(*r, *c).unwrap() == &(value + 1))
-            .flat_map(|(r, c)| find_trails2(grid, r, c, &(value + 1), &new_history))
-            .sorted()
-            .inspect(|&x| {
-                eprintln!("{x:?} {:?}", grid.iloc(x.0, x.1));
-            })
-            .unique()
-            .collect()
+        grid.neighbors(*row, *col)
+            .filter(|&(r, c)| grid.iloc(r, c) == Some(&(value + 1)))
+            .map(|(r, c)| count_trails(grid, &r, &c, &(value + 1)))
+            .sum()
     }
 }
 
@@ -148,14 +75,14 @@ pub fn find_trails2(
 //     todo!()
 // }
 
-pub fn sum_trailhead_scores(grid: &Grid, as_ratings: bool) -> u64 {
+pub fn sum_trailhead_scores(grid: &Grid<u8>, as_ratings: bool) -> u64 {
     let trailheads = find_trailheads_candidates(grid);
     // let score = trailheads.iter().map(|(r, c)| count_trails(grid, r, c, &0)).sum();
     // eprintln!("Starts: {:?}", trailheads);
     let x = if as_ratings {
         trailheads
             .iter()
-            .map(|(r, c)| find_trails2(grid, r, c, &0, &[(*r, *c)]).len())
+            .map(|(r, c)| count_trails(grid, r, c, &0))
             .sum::<usize>()
     } else {
         trailheads
@@ -172,6 +99,24 @@ pub fn main(input: &str, as_ratings: bool) -> u64 {
     sum_trailhead_scores(&grid, as_ratings)
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Grid<u8>) -> Option<Answer> {
+        Some(sum_trailhead_scores(input, false).into())
+    }
+
+    fn part2(input: &Grid<u8>) -> Option<Answer> {
+        Some(sum_trailhead_scores(input, true).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::main;
@@ -204,9 +149,9 @@ mod tests {
         assert_eq!(result, 36);
     }
 
-    // #[test]
-    // fn test_example_2_part_2() {
-    //     let result = main(INPUT_2, true);
-    //     assert_eq!(result, 81);
-    // }
+    #[test]
+    fn test_example_2_part_2() {
+        let result = main(INPUT_2, true);
+        assert_eq!(result, 81);
+    }
 }
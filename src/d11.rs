@@ -1,7 +1,20 @@
 use std::collections::HashMap;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub fn parse_input(input: &str) -> HashMap<u64, u64> {
-    input.split(" ").map(|x| (x.parse().unwrap(), 1)).collect()
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<HashMap<u64, u64>, ParseError> {
+    let source = Source::new(11, input);
+    input
+        .split_ascii_whitespace()
+        .map(|x| Ok((source.number(x)?, 1)))
+        .collect()
 }
 
 // pub fn blink(stones: &mut Vec<u64>) {
@@ -66,7 +79,7 @@ pub fn transform(stone: u64) -> (u64, Option<u64>) {
         (1, None)
     } else {
         let n_digits = stone.ilog10() + 1;
-        if n_digits % 2 == 0 {
+        if n_digits.is_multiple_of(2) {
             // even number of digits
             // left is the first half of the digits
             let divisor = 10u64.pow(n_digits / 2);
@@ -80,8 +93,8 @@ pub fn transform(stone: u64) -> (u64, Option<u64>) {
     }
 }
 
-pub fn main(input: &str, n_blinks: u64) -> u64 {
-    let mut stones = parse_input(input);
+pub fn count_stones(stones: &HashMap<u64, u64>, n_blinks: u64) -> u64 {
+    let mut stones = stones.clone();
     for _i in 0..n_blinks {
         stones = blink(&stones);
     }
@@ -89,6 +102,29 @@ pub fn main(input: &str, n_blinks: u64) -> u64 {
     stones.values().sum()
 }
 
+pub fn main(input: &str, n_blinks: u64) -> u64 {
+    let stones = parse_input(input);
+    count_stones(&stones, n_blinks)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = HashMap<u64, u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(count_stones(input, 25).into())
+    }
+
+    fn part2(input: &Self::Input) -> Option<Answer> {
+        Some(count_stones(input, 75).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d11::main;
@@ -6,142 +6,67 @@ group of connected Plants of the same type.
 Then, for each Region, we calculate the cost (of fencing)
 as each region's Area * Perimeter.
 */
-use std::collections::{HashSet, VecDeque};
-
-#[derive(Debug)]
-pub struct Field {
-    pub plots: Vec<Plot>,
-    pub n_rows: usize,
-    pub n_cols: usize,
-    // pub regions: HashMap<char, Vec<(usize, usize)>>,
-}
+use crate::{
+    solution::{Answer, Solution},
+    utils::{Grid, ParseError, Source},
+};
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-pub struct Plot {
-    pub label: char,
-    pub row: usize,
-    pub col: usize,
+pub fn parse_input(input: &str) -> Grid<char> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
-pub fn parse_input(input: &str) -> Field {
-    let mut plots = Vec::new();
-    let n_rows = input.matches("\n").count() + 1;
-    let mut n_cols = 0;
-    for (row, line) in input.lines().enumerate() {
-        n_cols = line.len();
-        for (col, label) in line.chars().enumerate() {
-            plots.push(Plot {
-                label,
-                row,
-                col,
-                // region: None,
-            });
-        }
-    }
-    Field {
-        plots,
-        n_rows,
-        n_cols,
-        // regions: HashMap::new(),
-    }
+pub fn try_parse_input(input: &str) -> Result<Grid<char>, ParseError> {
+    let (grid, _) = Grid::parse(&Source::new(12, input), input, &[], Some)?;
+    Ok(grid)
 }
 
-impl Field {
-    /*
-    The neighbors of a plot that have the same label.
-     */
-
-    pub fn get(&self, row: usize, col: usize) -> Option<&Plot> {
-        self.plots.get(row * self.n_cols + col)
-    }
-
-    pub fn neighbors(&self, plot: &Plot) -> Vec<Option<&Plot>> {
-        let mut neighbors = Vec::new();
-
-        if plot.row > 0 {
-            neighbors.push(self.get(plot.row - 1, plot.col));
-        }
-
-        if plot.row < self.n_rows - 1 {
-            neighbors.push(self.get(plot.row + 1, plot.col));
-        }
-
-        if plot.col > 0 {
-            neighbors.push(self.get(plot.row, plot.col - 1));
-        }
-        if plot.col < self.n_cols - 1 {
-            neighbors.push(self.get(plot.row, plot.col + 1));
-        }
-
-        neighbors
-    }
+pub fn fencing_price(field: &Grid<char>) -> u64 {
+    // https://advent-of-code.xavd.id/writeups/2024/day/12/ has a nice write up.
+    field
+        .regions()
+        .regions
+        .iter()
+        .map(|region| (region.area * region.perimeter) as u64)
+        .sum()
+}
 
-    pub fn matching_neighbors(&self, plot: &Plot) -> Vec<&Plot> {
-        self.neighbors(plot)
-            .iter()
-            .filter_map(|p| {
-                if let Some(p) = p {
-                    if p.label == plot.label {
-                        return Some(*p);
-                    }
-                }
-                None
-            })
-            .collect()
-    }
+/// Part 2: with the bulk discount, each straight side costs the same as one
+/// unit of fence.
+pub fn discounted_price(field: &Grid<char>) -> u64 {
+    field
+        .regions()
+        .regions
+        .iter()
+        .map(|region| (region.area * region.sides) as u64)
+        .sum()
 }
 
 pub fn main(input: &str) -> u64 {
     let field = parse_input(input);
-    let mut regions: Vec<HashSet<&Plot>> = Vec::new();
-    let mut all_points: HashSet<&Plot> = HashSet::new();
+    fencing_price(&field)
+}
 
-    // https://advent-of-code.xavd.id/writeups/2024/day/12/ has a nice write up.
-    for plot in field.plots.iter() {
-        if all_points.contains(&plot) {
-            continue;
-        }
-
-        // We're the root of a new region. Congrats.
-        let mut region = HashSet::new();
-        let mut queue = VecDeque::new();
-        queue.push_back(plot);
-
-        // Depth-first search over ...
-        while !queue.is_empty() {
-            let current = queue.pop_front().unwrap();
-            if region.contains(&current) {
-                continue;
-            }
-
-            region.insert(current);
-
-            for neighbor in field.matching_neighbors(current) {
-                queue.push_back(neighbor);
-            }
-        }
-
-        all_points.extend(&region);
-        regions.push(region);
-    }
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid<char>;
 
-    let mut result = 0;
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        try_parse_input(input)
+    }
 
-    for region in regions.iter() {
-        let mut perimeter = 0;
-        let area = region.len();
-        // let mut area = 0;
-        for plot in region.iter() {
-            perimeter += 4 - field.matching_neighbors(&plot).len();
-        }
-        result += perimeter * area;
+    fn part1(input: &Grid<char>) -> Option<Answer> {
+        Some(fencing_price(input).into())
     }
 
-    result as u64
+    fn part2(input: &Grid<char>) -> Option<Answer> {
+        Some(discounted_price(input).into())
+    }
 }
+
 #[cfg(test)]
 mod tests {
-    use crate::d12::main;
+    use crate::d12::{discounted_price, main, parse_input};
 
     const INPUT1: &str = "\
 AAAA
@@ -173,4 +98,12 @@ MMMISSJEEE";
         assert_eq!(main(INPUT2), 772);
         assert_eq!(main(INPUT3), 1930);
     }
+
+    #[test]
+    fn test_part_2_example() {
+        let price = |input| discounted_price(&parse_input(input));
+        assert_eq!(price(INPUT1), 80);
+        assert_eq!(price(INPUT2), 436);
+        assert_eq!(price(INPUT3), 1206);
+    }
 }
@@ -2,9 +2,16 @@ use itertools::Itertools;
 // https://adventofcode.com/2024/day/13
 use regex;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub const COST_A: u64 = 3;
 pub const COST_B: u64 = 1;
 pub const MAX_PUSHES: u64 = 100;
+/// Part 2 moves every prize this far along both axes.
+pub const PART_2_OFFSET: u64 = 10000000000000;
 
 #[derive(Debug)]
 pub struct Game {
@@ -17,6 +24,11 @@ pub struct Game {
 }
 
 pub fn parse_input(input: &str) -> Vec<Game> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<Game>, ParseError> {
+    let source = Source::new(13, input);
     let mut lines = input.lines();
     let mut games = Vec::new();
 
@@ -24,22 +36,24 @@ pub fn parse_input(input: &str) -> Vec<Game> {
     use regex::Regex;
     let button_re = Regex::new(r"Button [A|B]: X\+(\d+), Y\+(\d+)").unwrap();
     let price_re = Regex::new(r"Prize: X=(\d+), Y=(\d+)").unwrap();
+    let values = |re: &Regex, line, expected| {
+        let caps = re
+            .captures(line)
+            .ok_or_else(|| source.error(line, format!("expected '{expected}'")))?;
+        Ok::<_, ParseError>((
+            source.number(caps.get(1).unwrap().as_str())?,
+            source.number(caps.get(2).unwrap().as_str())?,
+        ))
+    };
 
     while let Some(a_line) = lines.next() {
-        let caps = button_re.captures(a_line).unwrap();
-        let a_x = caps[1].parse().expect("Failed to parse 'x' from A line");
-        let a_y = caps[2].parse().unwrap();
-        let b_line = lines.next().unwrap();
-        let price_line = lines.next().unwrap();
+        let (a_x, a_y) = values(&button_re, a_line, "Button A: X+.., Y+..")?;
+        let b_line = source.next(&mut lines, a_line, "a 'Button B' line")?;
+        let price_line = source.next(&mut lines, b_line, "a 'Prize' line")?;
         let _blank_line = lines.next().unwrap_or_default();
 
-        let caps = button_re.captures(b_line).unwrap();
-        let b_x = caps[1].parse().unwrap();
-        let b_y = caps[2].parse().unwrap();
-
-        let caps = price_re.captures(price_line).unwrap();
-        let x = caps[1].parse().unwrap();
-        let y = caps[2].parse().unwrap();
+        let (b_x, b_y) = values(&button_re, b_line, "Button B: X+.., Y+..")?;
+        let (x, y) = values(&price_re, price_line, "Prize: X=.., Y=..")?;
 
         games.push(Game {
             a_x,
@@ -50,7 +64,7 @@ pub fn parse_input(input: &str) -> Vec<Game> {
             y,
         });
     }
-    games
+    Ok(games)
 }
 
 impl Game {
@@ -89,17 +103,81 @@ impl Game {
         // });
         // best.cloned()
     }
+
+    /// Too many pushes for the naive search, so solve the two equations
+    /// directly (Cramer's rule):
+    ///
+    ///     na * a_x + nb * b_x = x
+    ///     na * a_y + nb * b_y = y
+    ///
+    /// There's no limit on the number of pushes. If A and B move the claw in
+    /// the same direction there isn't a unique solution; the inputs don't
+    /// seem to have any of those, so they're treated as unwinnable.
+    pub fn solve_with_offset(&self, offset: u64) -> Option<u64> {
+        let (a_x, a_y, b_x, b_y) = (
+            self.a_x as i128,
+            self.a_y as i128,
+            self.b_x as i128,
+            self.b_y as i128,
+        );
+        let (x, y) = ((self.x + offset) as i128, (self.y + offset) as i128);
+
+        let det = a_x * b_y - a_y * b_x;
+        if det == 0 {
+            return None;
+        }
+        let na = x * b_y - y * b_x;
+        let nb = a_x * y - a_y * x;
+        if na % det != 0 || nb % det != 0 || na / det < 0 || nb / det < 0 {
+            return None;
+        }
+
+        Some(COST_A * (na / det) as u64 + COST_B * (nb / det) as u64)
+    }
 }
 
+pub fn total_cost(games: &[Game]) -> u64 {
+    games.iter().filter_map(|g| g.solve()).sum()
+}
+
+pub fn total_cost_with_offset(games: &[Game], offset: u64) -> u64 {
+    games
+        .iter()
+        .filter_map(|g| g.solve_with_offset(offset))
+        .sum()
+}
+
+/// Part 1 with no `offset`, part 2 with `PART_2_OFFSET`.
 pub fn main(input: &str, offset: u64) -> u64 {
-    let _ = offset;
     let games = parse_input(input);
-    games.iter().filter_map(|g| g.solve()).sum()
+    if offset == 0 {
+        total_cost(&games)
+    } else {
+        total_cost_with_offset(&games, offset)
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Vec<Game>) -> Option<Answer> {
+        Some(total_cost(input).into())
+    }
+
+    fn part2(input: &Vec<Game>) -> Option<Answer> {
+        Some(total_cost_with_offset(input, PART_2_OFFSET).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::d13::main;
+    use crate::d13::{main, parse_input, PART_2_OFFSET};
 
     const INPUT: &str = "\
 Button A: X+94, Y+34
@@ -122,4 +200,13 @@ Prize: X=18641, Y=10279";
     fn test_example_1() {
         assert_eq!(main(INPUT, 0), 480);
     }
+
+    #[test]
+    fn test_example_2() {
+        // Without the offset the exact solution agrees with the search.
+        for game in parse_input(INPUT) {
+            assert_eq!(game.solve_with_offset(0), game.solve());
+        }
+        assert_eq!(main(INPUT, PART_2_OFFSET), 875318608908);
+    }
 }
@@ -1,30 +1,38 @@
-use std::collections;
+use crate::{
+    render::{self, Style},
+    solution::{Answer, Solution},
+    utils::{Grid, ParseError, Point, Source, Vector},
+};
 
 type State = Vec<((usize, usize), (i32, i32))>;
 
+/// The puzzle's bathroom, and how long part 1 waits.
+pub const WIDTH: usize = 101;
+pub const HEIGHT: usize = 103;
+pub const N_ITER: usize = 100;
+
 pub fn parse_input(input: &str) -> State {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<State, ParseError> {
+    let source = Source::new(14, input);
     let mut result: Vec<_> = Vec::new();
 
     for line in input.lines() {
-        let (p, v) = line.split_once(' ').expect("Missing position or velocity.");
-        let (px, py) = p
-            .trim_start_matches("p=")
-            .split_once(",")
-            .expect("Failed to parse position");
-        let (vx, vy) = v
-            .trim_start_matches("v=")
-            .split_once(",")
-            .expect("Failed to parse position");
-
-        let npx = px.parse().unwrap();
-        let npy = py.parse().unwrap();
-        let vpx = vx.parse().unwrap();
-        let vpy = vy.parse().unwrap();
+        let (p, v) = source.split_once(line, " ")?;
+        let (px, py) = source.split_once(p.trim_start_matches("p="), ",")?;
+        let (vx, vy) = source.split_once(v.trim_start_matches("v="), ",")?;
+
+        let npx = source.number(px)?;
+        let npy = source.number(py)?;
+        let vpx = source.number(vx)?;
+        let vpy = source.number(vy)?;
 
         result.push(((npx, npy), (vpx, vpy)))
     }
 
-    result
+    Ok(result)
 }
 
 pub fn shift_n(
@@ -34,9 +42,10 @@ pub fn shift_n(
     height: usize,
     n: i32,
 ) -> (usize, usize) {
-    let x = ((p.0 as i32) + (n * v.0)).rem_euclid(width as i32) as usize;
-    let y = ((p.1 as i32) + (n * v.1)).rem_euclid(height as i32) as usize;
-    (x, y)
+    // x is the column and y the row.
+    let velocity = Vector::new(v.1 as isize, v.0 as isize);
+    let moved = Point::new(p.1, p.0).wrapping_add(velocity * n as isize, height, width);
+    (moved.col, moved.row)
 }
 
 pub fn step_n(state: &State, width: usize, height: usize, n: i32) -> State {
@@ -51,31 +60,39 @@ pub fn step_n(state: &State, width: usize, height: usize, n: i32) -> State {
     next
 }
 
-pub fn format_grid(state: &State, width: usize, height: usize) {
-    let mut counts = collections::HashMap::new();
-    for (p, _v) in state.iter() {
-        *counts.entry(p).or_insert(0) += 1;
+/// How many robots are on each tile.
+pub fn robots(state: &State, width: usize, height: usize) -> Grid<u32> {
+    let mut grid = Grid::filled(height, width, 0);
+    for ((x, y), _v) in state.iter() {
+        grid[Point::new(*y, *x)] += 1;
     }
+    grid
+}
 
-    let mut result = String::new();
-    for i in 0..height {
-        for j in 0..width {
-            let v = counts.get(&(j, i));
-            if let Some(c) = v {
-                result.push(char::from_digit(*c, 10).unwrap());
-            } else {
-                result.push('.');
-            }
-        }
-        result.push('\n');
+/// The robots at every step from 0 to `n` inclusive.
+pub fn frames(
+    state: &State,
+    width: usize,
+    height: usize,
+    n: usize,
+) -> impl Iterator<Item = Grid<u32>> + '_ {
+    (0..=n).map(move |i| robots(&step_n(state, width, height, i as i32), width, height))
+}
+
+/// For `robots`.
+pub fn style(count: &u32) -> Style {
+    match count {
+        0 => Style::new('.', render::BLACK),
+        n => Style::new(char::from_digit((*n).min(9), 10).unwrap(), render::GREEN),
     }
+}
 
-    eprintln!("{result}");
+pub fn format_grid(state: &State, width: usize, height: usize) -> String {
+    render::text(&robots(state, width, height), style)
 }
 
-pub fn main(input: &str, n_iter: usize, width: usize, height: usize) -> u64 {
-    let mut state = parse_input(input);
-    state = step_n(&state, width, height, n_iter as i32);
+pub fn safety_factor(state: &State, n_iter: usize, width: usize, height: usize) -> u64 {
+    let state = step_n(state, width, height, n_iter as i32);
     // for _i in 0..n_iter {
     //     state = step(&state, width, height);
     //     // format_grid(&state, width, height);
@@ -106,6 +123,11 @@ pub fn main(input: &str, n_iter: usize, width: usize, height: usize) -> u64 {
     quadrants.iter().product()
 }
 
+pub fn main(input: &str, n_iter: usize, width: usize, height: usize) -> u64 {
+    let state = parse_input(input);
+    safety_factor(&state, n_iter, width, height)
+}
+
 fn var(x: &[f64]) -> f64 {
     let n = x.len() as f64;
     let mu: f64 = x.iter().sum::<f64>() / n;
@@ -113,7 +135,7 @@ fn var(x: &[f64]) -> f64 {
     d2.iter().sum::<f64>() / n
 }
 
-pub fn find_tree(input: &str, width: usize, height: usize) -> u64 {
+pub fn find_tree(state: &State, width: usize, height: usize) -> u64 {
     /*
     wow: https://www.reddit.com/r/adventofcode/comments/1he0asr/comment/m1zzfsh/
 
@@ -125,12 +147,11 @@ pub fn find_tree(input: &str, width: usize, height: usize) -> u64 {
     */
 
     let n_max = width.max(height);
-    let state = parse_input(input);
     let mut xs: Vec<f64> = Vec::with_capacity(n_max);
     let mut ys: Vec<_> = Vec::with_capacity(n_max);
 
     for n in 0..=n_max {
-        let staten = step_n(&state, width, height, n as i32);
+        let staten = step_n(state, width, height, n as i32);
         let x: Vec<_> = staten.iter().map(|x| x.0 .0 as f64).collect();
         xs.push(var(&x));
 
@@ -138,16 +159,44 @@ pub fn find_tree(input: &str, width: usize, height: usize) -> u64 {
         ys.push(var(&y));
     }
 
-    // ys.iter().arg
+    // x repeats every `width` steps and y every `height` steps.
+    let argmin = |v: &[f64]| {
+        (0..v.len())
+            .min_by(|&i, &j| v[i].total_cmp(&v[j]))
+            .expect("No steps")
+    };
+    let tx = argmin(&xs[..width]);
+    let ty = argmin(&ys[..height]);
+
+    // The first t with t = tx (mod width) and t = ty (mod height).
+    (0..width * height)
+        .find(|t| t % width == tx && t % height == ty)
+        .expect("Width and height should be coprime") as u64
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = State;
+
+    fn parse(input: &str) -> Result<State, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &State) -> Option<Answer> {
+        Some(safety_factor(input, N_ITER, WIDTH, HEIGHT).into())
+    }
 
-    todo!()
+    fn part2(input: &State) -> Option<Answer> {
+        Some(find_tree(input, WIDTH, HEIGHT).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::d14::parse_input;
 
-    use super::{main, step_n};
+    use super::{find_tree, main, step_n};
 
     const INPUT: &str = "\
 p=0,4 v=3,-3
@@ -180,4 +229,25 @@ p=9,5 v=-3,-3";
         assert_eq!(step_n(&state, width, height, 4)[10].0, (10, 6));
         assert_eq!(step_n(&state, width, height, 5)[10].0, (1, 3));
     }
+
+    #[test]
+    fn test_find_tree() {
+        // Robots that are all bunched together after 1234 steps, and spread
+        // out the rest of the time.
+        let mut seed: i32 = 7;
+        let mut random = |n: i32| {
+            seed = (seed * 1103 + 12345).rem_euclid(65536);
+            seed % n
+        };
+        let bunched: Vec<_> = (0..200)
+            .map(|_| {
+                let p = (50 + random(5) as usize, 50 + random(5) as usize);
+                let v = (random(201) - 100, random(201) - 100);
+                (p, v)
+            })
+            .collect();
+        let state = step_n(&bunched, 101, 103, -1234);
+
+        assert_eq!(find_tree(&state, 101, 103), 1234);
+    }
 }
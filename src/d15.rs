@@ -1,4 +1,10 @@
-#[derive(PartialEq, Debug)]
+use crate::{
+    render::{self, Style},
+    solution::{Answer, Solution},
+    utils::{Direction, Grid, ParseError, Point, Source},
+};
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Object {
     Wall,
     Box,
@@ -6,67 +12,43 @@ pub enum Object {
     Robot,
 }
 
-#[derive(PartialEq, Debug, Copy, Clone)]
-pub enum Move {
-    U,
-    D,
-    L,
-    R,
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Map {
     pub grid: Vec<Object>,
-    pub moves: Vec<Move>,
+    pub moves: Vec<Direction>,
     pub n_rows: usize, // including walls
     pub n_cols: usize, // including walls
 }
 
 pub fn parse_input(input: &str) -> Map {
-    let mut grid = Vec::new();
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Map, ParseError> {
+    let source = Source::new(15, input);
+    let (warehouse, directions) = source.split_blank_line(input)?;
+    let (grid, marks) = Grid::parse(&source, warehouse, &['@'], |c| match c {
+        '#' => Some(Object::Wall),
+        'O' => Some(Object::Box),
+        '.' => Some(Object::Empty),
+        '@' => Some(Object::Robot),
+        _ => None,
+    })?;
+    marks.one(&source, '@', "a robot")?;
+
     let mut moves = Vec::new();
-    let lines = input.lines();
-    let mut n_rows = 0;
-    let mut n_cols = 0;
-
-    for (i, line) in lines.enumerate() {
-        if line.starts_with('#') {
-            n_rows = i;
-            n_cols = line.len();
-            for c in line.chars() {
-                let value = match c {
-                    '#' => Object::Wall,
-                    'O' => Object::Box,
-                    '.' => Object::Empty,
-                    '@' => Object::Robot,
-                    _ => panic!("Unexpected character"),
-                };
-                grid.push(value);
-            }
-        } else if line.is_empty() {
-            continue;
-        } else {
-            for c in line.chars() {
-                if c != '\n' {
-                    let v = match c {
-                        '^' => Move::U,
-                        'v' => Move::D,
-                        '<' => Move::L,
-                        '>' => Move::R,
-                        _ => panic!("Unexpected character in move set."),
-                    };
-                    moves.push(v);
-                }
-            }
+    for line in directions.lines() {
+        for (j, c) in line.char_indices() {
+            moves.push(Direction::from_arrow(c).ok_or_else(|| source.unexpected_char(line, j))?);
         }
     }
 
-    Map {
-        grid,
+    Ok(Map {
+        n_rows: grid.n_rows(),
+        n_cols: grid.n_cols(),
+        grid: grid.into_values(),
         moves,
-        n_rows: n_rows + 1,
-        n_cols,
-    }
+    })
 }
 
 impl Map {
@@ -93,16 +75,23 @@ impl Map {
     // pub fn get_right(&self, row: usize, col: usize) -> Option<&Object> {
     //     self.get(row, col.wrapping_add(1))
     // }
-    // pub fn get_direction(&self, direction: Move, row: usize, col: usize) -> Option<&Object> {
+    // pub fn get_direction(&self, direction: Direction, row: usize, col: usize) -> Option<&Object> {
     //     match direction {
-    //         Move::U => self.get_up(row, col),
-    //         Move::D => self.get_down(row, col),
-    //         Move::L => self.get_left(row, col),
-    //         Move::R => self.get_right(row, col),
+    //         Direction::N => self.get_up(row, col),
+    //         Direction::S => self.get_down(row, col),
+    //         Direction::W => self.get_left(row, col),
+    //         Direction::E => self.get_right(row, col),
     //     }
     // }
 
     pub fn step(&mut self) {
+        self.step_with(|_| {});
+    }
+
+    /// Like `step`, calling `on_move` with the starting map and again after
+    /// every move.
+    pub fn step_with(&mut self, mut on_move: impl FnMut(&Map)) {
+        on_move(self);
         // find the position of the robot.
         let index = self.grid.iter().position(|r| *r == Object::Robot).unwrap();
 
@@ -126,12 +115,10 @@ impl Map {
             // eprintln!("Move {direction:?} from ({row}, {col})");
 
             loop {
-                (pending_row, pending_col) = match direction {
-                    Move::U => (pending_row - 1, pending_col),
-                    Move::D => (pending_row + 1, pending_col),
-                    Move::L => (pending_row, pending_col - 1),
-                    Move::R => (pending_row, pending_col + 1),
-                };
+                (pending_row, pending_col) = Point::new(pending_row, pending_col)
+                    .step(*direction)
+                    .expect("The warehouse is walled in.")
+                    .into();
                 let new_object = self.get(pending_row, pending_col).unwrap();
                 // eprintln!("Hit {new_object:?} @ ({pending_col}, {pending_col})");
                 match new_object {
@@ -167,12 +154,10 @@ impl Map {
             }
             if let Some((next_row, next_col)) = pending_robot {
                 // invert direction
-                let (from_row, from_col) = match direction {
-                    Move::U => (next_row + 1, next_col),
-                    Move::D => (next_row - 1, next_col),
-                    Move::L => (next_row, next_col + 1),
-                    Move::R => (next_row, next_col - 1),
-                };
+                let (from_row, from_col) = Point::new(next_row, next_col)
+                    .step(direction.opposite())
+                    .expect("The robot came from somewhere.")
+                    .into();
                 // eprintln!("Update robot! ({}, {}) -> ({}, {})", from_row, from_col, next_row, next_col);
                 self.set(from_row, from_col, Object::Empty);
                 self.set(next_row, next_col, Object::Robot);
@@ -183,7 +168,7 @@ impl Map {
             if let Some(next_box) = pending_box {
                 self.set(next_box.0, next_box.1, Object::Box);
             }
-            // self.print();
+            on_move(self);
         }
     }
 
@@ -204,24 +189,23 @@ impl Map {
         result
     }
 
+    pub fn grid(&self) -> Grid<Object> {
+        Grid::new(self.n_rows, self.n_cols, self.grid.clone())
+    }
+
     pub fn print(&self) {
-        let mut buf = String::new();
+        eprint!("{}", render::text(&self.grid(), Object::style));
+    }
+}
 
-        for (index, value) in self.grid.iter().enumerate() {
-            if index > 0 && index % self.n_cols == 0 {
-                buf.push('\n');
-            }
-            // let (row, col) = (index / self.n_cols, index % self.n_cols);
-            let c = match value {
-                Object::Box => 'O',
-                Object::Empty => '.',
-                Object::Robot => '@',
-                Object::Wall => '#',
-            };
-            buf.push(c);
+impl Object {
+    pub fn style(&self) -> Style {
+        match self {
+            Object::Box => Style::new('O', render::YELLOW),
+            Object::Empty => Style::new('.', render::BLACK),
+            Object::Robot => Style::new('@', render::RED),
+            Object::Wall => Style::new('#', render::GREY),
         }
-
-        eprintln!("{buf}");
     }
 }
 
@@ -231,9 +215,28 @@ pub fn main(input: &str) -> u64 {
     map.score()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Map, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Map) -> Option<Answer> {
+        let mut map = input.clone();
+        map.step();
+        Some(map.score().into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::d15::{parse_input, Move, Object};
+    use crate::{
+        d15::{parse_input, try_parse_input, Object},
+        utils::Direction,
+    };
 
     const INPUT_SMALL: &str = "\
 ########
@@ -287,11 +290,30 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
     fn test_example_1() {
         let mut result = parse_input(INPUT);
         assert_eq!(result.grid[0], Object::Wall);
-        assert_eq!(result.moves[0], Move::L);
+        assert_eq!(result.moves[0], Direction::W);
         assert_eq!(result.n_rows, 10);
         assert_eq!(result.n_cols, 10);
         result.step();
 
         assert_eq!(result.score(), 10092);
     }
+
+    #[test]
+    fn test_crlf() {
+        let crlf = INPUT_SMALL.replace('\n', "\r\n");
+        let mut result = parse_input(&crlf);
+        result.step();
+        assert_eq!(result.score(), 2028);
+
+        let error = try_parse_input("#.@#\n<>").err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (1, "#.@#"));
+    }
+
+    #[test]
+    fn test_robots() {
+        let error = try_parse_input("#..#\n\n<>").err().unwrap();
+        assert_eq!(error.message, "expected a robot (@)");
+        let error = try_parse_input("#@@#\n\n<>").err().unwrap();
+        assert_eq!(error.message, "expected a robot (@) only once");
+    }
 }
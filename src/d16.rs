@@ -6,9 +6,12 @@ Are there pathological cases where an "extra" turn
 is worth it?
 
 */
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap},
+use std::collections::HashSet;
+
+use crate::{
+    search::{self, Search},
+    solution::{Answer, Solution},
+    utils::{Direction, Grid, ParseError, Point, Source},
 };
 
 pub struct Map {
@@ -37,168 +40,130 @@ What tricks can we pull to avoid an exhaustive search.
      value theorem that says it doesn't matter which way you go?
 */
 
-// pub fn search(valid: Vec<(usize, usize)>, start: (usize, usize), end: (usize, usize)) -> Vec<Vec<(usize, usize)>> {
-//     todo!()
-// }
+/// Where we are and which way we're facing.
+type State = ((usize, usize), Direction);
+
+/// Stepping forward costs 1, and turning either way costs 1000.
+fn moves(valid: &HashSet<(usize, usize)>, &(position, direction): &State) -> Vec<(State, u64)> {
+    let mut result = vec![
+        ((position, direction.turn_left()), 1000),
+        ((position, direction.turn_right()), 1000),
+    ];
+    let forward = Point::from(position)
+        .step(direction)
+        .map(<(usize, usize)>::from);
+    if let Some(forward) = forward.filter(|p| valid.contains(p)) {
+        result.push(((forward, direction), 1));
+    }
+    result
+}
 
-#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy)]
-pub enum Direction {
-    N,
-    S,
-    E,
-    W,
+/// Every state's cheapest score from the start (facing east), and the states
+/// at the end in each direction.
+fn explore(
+    start: (usize, usize),
+    end: (usize, usize),
+    valid: &[(usize, usize)],
+) -> (Search<State>, [State; 4]) {
+    let valid: HashSet<_> = valid.iter().copied().collect();
+    let search = search::dijkstra((start, Direction::E), |state| moves(&valid, state));
+    (search, Direction::CARDINAL.map(|d| (end, d)))
 }
 
+/// The best score, and the direction after each move (turns included).
+/// `None` if the end can't be reached.
 pub fn search(
     start: (usize, usize),
     end: (usize, usize),
-    valid: Vec<(usize, usize)>,
-) -> (u64, Vec<Direction>) {
-    // Rust uses a max heap, i.e. pop will return the highest value.
-    // We want to minimize our score / cost here, we we generally want to pop the lowest value.
-    // So all push operations to the queue should wrap the score in a Reverse.
-    //
-    // - initial score 0
-    // - initial best score: u64::MAX
-
-    let mut q: BinaryHeap<_> = BinaryHeap::new();
-    let mut best_score = u64::MAX;
-    let mut paths = Vec::new();
-    let mut visited = HashMap::new();
-    // let mut direction = Direction::E;
-
-    q.push((Reverse(0u64), start, Direction::E, vec![]));
-
-    let mut i = 0;
-    while !q.is_empty() {
-        // Score is the distance, including the cost of turns, to the end
-        // position is the (row, col)
-        // direction is the Direction we're facing
-        let (score, position, direction, path) = q.pop().unwrap();
-
-        i += 1;
-        if i > 10000000 {
-            break;
-        }
-
-        if score.0 > best_score {
-            // we can't beat our best score from here, so give up.
-            break;
-        }
-
-        visited.entry((position, direction)).or_insert(score.0);
-        if visited.get(&(position, direction)).unwrap() < &score.0 {
-            // We've already visited here facing this direction, but with a lower cost. Give up.
-            continue;
-        }
-
-        // eprintln!("i={i} score={score:?} position={position:?} direction={direction:?}");
-        // We get to (13, 13), but only (try to) head E from there.
-
-        if position == end {
-            // eprintln!("{path:?}");
-            // assert!(false);
-            best_score = score.0;
-            paths.push(path.clone());
-        }
+    valid: &[(usize, usize)],
+) -> Option<(u64, Vec<Direction>)> {
+    let (search, ends) = explore(start, end, valid);
+    let (end, score) = search.nearest(&ends)?;
+    let path = search.path(end)?;
+    Some((score, path.iter().skip(1).map(|&(_, d)| d).collect()))
+}
 
-        // direction after turning right
-        let right = match direction {
-            Direction::N => Direction::E,
-            Direction::E => Direction::S,
-            Direction::S => Direction::W,
-            Direction::W => Direction::N,
-        };
-        // direction after turning left
-        let left = match direction {
-            Direction::N => Direction::W,
-            Direction::W => Direction::S,
-            Direction::S => Direction::E,
-            Direction::E => Direction::N,
-        };
-
-        let forward_position = match direction {
-            Direction::N => (position.0 - 1, position.1),
-            Direction::S => (position.0 + 1, position.1),
-            Direction::E => (position.0, position.1 + 1),
-            Direction::W => (position.0, position.1 - 1),
-        };
-
-        if valid.contains(&forward_position) {
-            q.push((
-                Reverse(score.0 + 1),
-                forward_position,
-                direction,
-                [path.clone(), vec![direction]].concat(),
-            ));
-        }
-        q.push((
-            Reverse(score.0 + 1000),
-            position,
-            left,
-            [path.clone(), vec![left]].concat(),
-        ));
-        q.push((
-            Reverse(score.0 + 1000),
-            position,
-            right,
-            [path.clone(), vec![right]].concat(),
-        ));
-    }
-    (best_score, paths[paths.len() - 1].clone())
+/// Part 2: how many tiles are on at least one of the best paths.
+pub fn count_best_seats(
+    start: (usize, usize),
+    end: (usize, usize),
+    valid: &[(usize, usize)],
+) -> usize {
+    let (search, ends) = explore(start, end, valid);
+    let tiles: HashSet<_> = search
+        .on_shortest_paths(&ends)
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect();
+    tiles.len()
 }
 
 pub fn neighbors_of(valid: &[(usize, usize)], current: (usize, usize)) -> Vec<&(usize, usize)> {
     // might want to sort this, by the minimum number of turns?
     // then we'll need to pass around a direction we're coming from.
+    let current = Point::from(current);
     valid
         .iter()
-        .filter(|(a, b)| {
-            current == (a + 1, *b)
-                || current == (a - 1, *b)
-                || (current == (*a, b - 1))
-                || (current == (*a, b + 1))
-        })
+        .filter(|&&p| Point::from(p).manhattan(current) == 1)
         .collect()
 }
 
 pub fn parse_input(input: &str) -> Map {
-    let mut valid = Vec::new();
-    let mut current: Option<(usize, usize)> = None;
-    let mut end: Option<(usize, usize)> = None;
-    for (r, line) in input.lines().enumerate() {
-        for (c, char) in line.chars().enumerate() {
-            if char == 'S' {
-                current = Some((r, c));
-            } else if char == 'E' {
-                end = Some((r, c));
-            }
-            if char != '#' {
-                valid.push((r, c));
-            }
-        }
-    }
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
 
-    Map {
-        valid,
-        current: current.unwrap(),
-        end: end.unwrap(),
-    }
+pub fn try_parse_input(input: &str) -> Result<Map, ParseError> {
+    let source = Source::new(16, input);
+    let (grid, marks) = Grid::parse(&source, input, &['S', 'E'], |c| match c {
+        '#' => Some(false),
+        '.' | 'S' | 'E' => Some(true),
+        _ => None,
+    })?;
+
+    Ok(Map {
+        valid: grid
+            .iter()
+            .filter(|(_, &open)| open)
+            .map(|(p, _)| p)
+            .collect(),
+        current: marks.one(&source, 'S', "a start")?,
+        end: marks.one(&source, 'E', "an end")?,
+    })
 }
 
 pub fn main(input: &str) -> u64 {
     let map = parse_input(input);
     // eprintln!("valid={:?}", map.valid);
-    let (score, _path) = search(map.current, map.end, map.valid);
+    let (score, _path) = search(map.current, map.end, &map.valid).expect("No way to the end.");
     // eprintln!("path={path:?}");
     score
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Map, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Map) -> Option<Answer> {
+        let (score, _path) =
+            search(input.current, input.end, &input.valid).expect("No way to the end.");
+        Some(score.into())
+    }
+
+    fn part2(input: &Map) -> Option<Answer> {
+        Some(count_best_seats(input.current, input.end, &input.valid).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::d16::parse_input;
 
-    use super::main;
+    use super::{count_best_seats, main, search};
 
     const INPUT_1: &str = "\
 ###############
@@ -251,4 +216,19 @@ mod tests {
         let result = main(INPUT_2);
         assert_eq!(result, 11048);
     }
+
+    #[test]
+    fn test_best_seats() {
+        for (input, expected) in [(INPUT_1, 45), (INPUT_2, 64)] {
+            let m = parse_input(input);
+            assert_eq!(count_best_seats(m.current, m.end, &m.valid), expected);
+        }
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let m = parse_input("#####\n#S#E#\n#####");
+        assert_eq!(search(m.current, m.end, &m.valid), None);
+        assert_eq!(count_best_seats(m.current, m.end, &m.valid), 0);
+    }
 }
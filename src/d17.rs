@@ -1,3 +1,8 @@
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub enum OpKind {
     Literal,
     Combo,
@@ -21,6 +26,7 @@ pub enum Op {
     cdv, // 7: a / (pow**combo) -> c
 }
 
+#[derive(Clone)]
 pub struct Program {
     pub a: u64,
     pub b: u64,
@@ -30,51 +36,36 @@ pub struct Program {
 }
 
 pub fn parse_input(input: &str) -> Program {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Program, ParseError> {
+    let source = Source::new(17, input);
     let mut lines = input.lines();
-    let a = lines
-        .next()
-        .unwrap()
-        .split(": ")
-        .last()
-        .unwrap()
-        .parse()
-        .unwrap();
-    let b = lines
-        .next()
-        .unwrap()
-        .split(": ")
-        .last()
-        .unwrap()
-        .parse()
-        .unwrap();
-    let c = lines
-        .next()
-        .unwrap()
-        .split(": ")
-        .last()
-        .unwrap()
-        .parse()
-        .unwrap();
+    let a_line = source.next(&mut lines, source.end(), "'Register A: ..'")?;
+    let a = source.number(source.split_once(a_line, ": ")?.1)?;
+    let b_line = source.next(&mut lines, a_line, "'Register B: ..'")?;
+    let b = source.number(source.split_once(b_line, ": ")?.1)?;
+    let c_line = source.next(&mut lines, b_line, "'Register C: ..'")?;
+    let c = source.number(source.split_once(c_line, ": ")?.1)?;
 
     lines.next(); // blank
 
-    let opcodes: Vec<_> = lines
-        .next()
-        .unwrap()
-        .split(": ")
-        .last()
-        .unwrap()
+    let program_line = source.next(&mut lines, c_line, "'Program: ..'")?;
+    let opcodes: Vec<_> = source
+        .split_once(program_line, ": ")?
+        .1
         .split(",")
-        .map(|c| c.parse().unwrap())
-        .collect();
+        .map(|c| source.number(c))
+        .collect::<Result<_, _>>()?;
 
-    Program {
+    Ok(Program {
         a,
         b,
         c,
         program: opcodes,
         instruction_pointer: 0,
-    }
+    })
 }
 
 impl Program {
@@ -153,11 +144,30 @@ impl Program {
     }
 }
 
+pub fn format_output(output: &[u64]) -> String {
+    let x: Vec<_> = output.iter().map(|x| x.to_string()).collect();
+    x.join(",")
+}
+
 pub fn main(input: &str) -> String {
     let mut program = parse_input(input);
     let output = program.run();
-    let x: Vec<_> = output.iter().map(|x| x.to_string()).collect();
-    x.join(",")
+    format_output(&output)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Program;
+
+    fn parse(input: &str) -> Result<Program, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Program) -> Option<Answer> {
+        let mut program = input.clone();
+        Some(format_output(&program.run()).into())
+    }
 }
 
 #[cfg(test)]
@@ -1,5 +1,16 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashSet;
 
+use crate::{
+    search,
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
+/// The puzzle's memory space, and how many bytes part 1 lets fall.
+pub const SIZE: usize = 71;
+pub const N_STEPS: usize = 1024;
+
+#[derive(Clone)]
 pub struct Grid {
     incoming: Vec<(usize, usize)>,
     n_rows: usize,
@@ -9,6 +20,9 @@ pub struct Grid {
 
 impl Grid {
     pub fn get(&self, row: usize, col: usize) -> Option<&bool> {
+        if row >= self.n_rows || col >= self.n_cols {
+            return None;
+        }
         let index = row * self.n_cols + col;
         self.is_safe.get(index)
     }
@@ -44,73 +58,108 @@ impl Grid {
 }
 
 impl Grid {
-    pub fn run(&mut self, n_steps: usize) -> Vec<(usize, usize)> {
+    /// The shortest path from the top left corner to the bottom right one,
+    /// both included, once the first `n_steps` bytes have fallen. `None` if
+    /// they've cut the exit off.
+    pub fn run(&mut self, n_steps: usize) -> Option<Vec<(usize, usize)>> {
         for i in 0..n_steps {
             let (col, row) = self.incoming[i];
             self.set(row, col, false);
         }
 
-        // (0, 0) -> (n_rows, n_cols)
         let target = (self.n_rows - 1, self.n_cols - 1);
-        let mut queue = VecDeque::new();
-        queue.push_back((0, 0));
-        let mut visited = HashMap::new();
-        // visited.insert((0, 0));
-
-        while !queue.is_empty() {
-            let (row, col) = queue.pop_front().unwrap();
-            let neighbors = self.safe_neighbors(row, col);
-            for neighbor in neighbors.iter() {
-                if *neighbor == target {
-                    visited.insert(*neighbor, (row, col));
-                    break;
-                }
+        let search = search::bfs((0, 0), |&(row, col)| self.safe_neighbors(row, col));
+        search.path(&target)
+    }
 
-                if !visited.contains_key(neighbor) {
-                    visited.insert(*neighbor, (row, col));
-                    queue.push_back(*neighbor);
-                }
-            }
-        }
+    /// Whether the first `n_bytes` bytes to fall cut the exit off, leaving the
+    /// grid as it is.
+    fn is_cut_off(&self, n_bytes: usize) -> bool {
+        let fallen: HashSet<_> = self.incoming[..n_bytes]
+            .iter()
+            .map(|&(col, row)| (row, col))
+            .collect();
+        let target = (self.n_rows - 1, self.n_cols - 1);
+        let search = search::bfs((0, 0), |&(row, col)| {
+            let mut next = self.safe_neighbors(row, col);
+            next.retain(|p| !fallen.contains(p));
+            next
+        });
+        search.distance(&target).is_none()
+    }
 
-        // now go back from the target to the start.
-        let mut path = Vec::new();
-        let mut node = target;
-        while node != (0, 0) {
-            node = visited[&node];
-            path.push(node);
+    /// Part 2: the first byte to cut the exit off, as it came in (x, y).
+    pub fn first_blocker(&self) -> Option<(usize, usize)> {
+        // Once the exit is cut off it stays that way, so binary search for
+        // the fewest bytes that do it. `lo` bytes leave a way out, and `hi`
+        // bytes (one past them all, at first) don't.
+        let (mut lo, mut hi) = (0, self.incoming.len() + 1);
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if self.is_cut_off(mid) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
         }
-
-        path.reverse();
-        path
+        self.incoming.get(hi - 1).copied()
     }
 }
 
 pub fn parse_input(input: &str, n_rows: usize, n_cols: usize) -> Grid {
+    try_parse_input(input, n_rows, n_cols).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str, n_rows: usize, n_cols: usize) -> Result<Grid, ParseError> {
+    let source = Source::new(18, input);
     let incoming: Vec<_> = input
         .lines()
-        .map(|line| line.split_once(",").unwrap())
-        .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
-        .collect();
+        .map(|line| {
+            let (a, b) = source.split_once(line, ",")?;
+            Ok((source.number(a)?, source.number(b)?))
+        })
+        .collect::<Result<_, ParseError>>()?;
     let size = n_cols * n_rows;
 
-    Grid {
+    Ok(Grid {
         incoming,
         n_rows,
         n_cols,
         is_safe: vec![true; size],
-    }
+    })
 }
 
-pub fn main(input: &str, n_rows: usize, n_cols: usize, n_steps: usize) -> usize {
+/// The number of steps to the exit, if there's still a way.
+pub fn main(input: &str, n_rows: usize, n_cols: usize, n_steps: usize) -> Option<usize> {
     let mut state = parse_input(input, n_rows, n_cols);
-    let path = state.run(n_steps);
-    path.len()
+    let path = state.run(n_steps)?;
+    Some(path.len() - 1)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Grid, ParseError> {
+        try_parse_input(input, SIZE, SIZE)
+    }
+
+    fn part1(input: &Grid) -> Option<Answer> {
+        let mut grid = input.clone();
+        let path = grid.run(N_STEPS).expect("No way to the exit.");
+        Some((path.len() - 1).into())
+    }
+
+    fn part2(input: &Grid) -> Option<Answer> {
+        let (x, y) = input.first_blocker().expect("The exit is never cut off.");
+        Some(format!("{x},{y}").into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_input;
+    use super::{main, parse_input};
 
     const INPUT: &str = "\
 5,4
@@ -143,7 +192,18 @@ mod tests {
     fn test_example_1() {
         let mut state = parse_input(INPUT, 7, 7);
         assert_eq!(state.incoming[0], (5, 4));
-        let path = state.run(12);
-        assert_eq!(path.len(), 22);
+        let path = state.run(12).unwrap();
+        assert_eq!(path.len() - 1, 22);
+        assert_eq!(main(INPUT, 7, 7, 12), Some(22));
+        // The 21st byte cuts the exit off.
+        assert_eq!(main(INPUT, 7, 7, 21), None);
+    }
+
+    #[test]
+    fn test_first_blocker() {
+        let state = parse_input(INPUT, 7, 7);
+        assert_eq!(state.first_blocker(), Some((6, 1)));
+        assert_eq!(parse_input("", 7, 7).first_blocker(), None);
+        assert_eq!(parse_input("6,6", 7, 7).first_blocker(), Some((6, 6)));
     }
 }
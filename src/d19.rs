@@ -9,11 +9,28 @@
 
 use std::collections::HashMap;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub fn parse_input(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let stock: Vec<_> = input.lines().next().unwrap().split(", ").collect();
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let source = Source::new(19, input);
+    let mut lines = input.lines();
+    let stock_line = source.next(&mut lines, source.end(), "the available towels")?;
+    let stock: Vec<_> = stock_line.split(", ").collect();
 
-    let designs: Vec<_> = input.lines().skip(2).collect();
-    (stock, designs)
+    let blank = source.next(&mut lines, stock_line, "a blank line")?;
+    if !blank.is_empty() {
+        return Err(source.error(blank, "expected a blank line"));
+    }
+
+    let designs: Vec<_> = lines.collect();
+    Ok((stock, designs))
 }
 
 pub fn is_possible<'a>(
@@ -50,6 +67,21 @@ pub fn main(input: &str) -> usize {
         .count()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        try_parse_input(input)?;
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &String) -> Option<Answer> {
+        Some(main(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -1,81 +1,64 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+
+use crate::{
+    search,
+    solution::{Answer, Solution},
+    utils::{self, Direction, Grid, ParseError, Source, Vector},
+};
 
 type Point = (usize, usize);
 type Points = Vec<Point>;
 
 pub fn parse_input(input: &str) -> (Points, Point, Point) {
-    let mut result = Vec::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
-            if c == '.' || c == 'S' || c == 'E' {
-                result.push((row, col));
-            }
-            if c == 'S' {
-                start = (row, col);
-            } else if c == 'E' {
-                end = (row, col)
-            }
-        }
-    }
-    (result, start, end)
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<(Points, Point, Point), ParseError> {
+    let source = Source::new(20, input);
+    let (grid, marks) = Grid::parse(&source, input, &['S', 'E'], |c| match c {
+        '#' => Some(false),
+        '.' | 'S' | 'E' => Some(true),
+        _ => None,
+    })?;
+
+    let result = grid
+        .iter()
+        .filter(|(_, &open)| open)
+        .map(|(p, _)| p)
+        .collect();
+    Ok((
+        result,
+        marks.one(&source, 'S', "a start")?,
+        marks.one(&source, 'E', "an end")?,
+    ))
 }
 
 pub fn neighbors_of(point: &Point, valid: &Points) -> Points {
-    let neighbors = [
-        (point.0 - 1, point.1),
-        (point.0 + 1, point.1),
-        (point.0, point.1 - 1),
-        (point.0, point.1 + 1),
-    ];
-    neighbors
+    Direction::CARDINAL
         .iter()
+        .filter_map(|&d| utils::Point::from(*point).step(d))
+        .map(Point::from)
         .filter(|n| valid.contains(n))
-        .copied()
         .collect()
 }
 
-pub fn search(start: &Point, end: &Point, valid: &Points) -> (Points, HashMap<Point, usize>) {
-    let mut queue = VecDeque::new();
-    queue.push_back(*start);
-    let mut visited = HashMap::new();
-
-    while !queue.is_empty() {
-        let point = queue.pop_front().unwrap();
-        let neighbors = neighbors_of(&point, valid);
-        for neighbor in neighbors.iter() {
-            if neighbor == end {
-                visited.insert(*neighbor, point);
-                break;
-            }
-
-            if !visited.contains_key(neighbor) {
-                visited.insert(*neighbor, point);
-                queue.push_back(*neighbor);
-            }
-        }
-    }
-
-    // now go back from the target to the start.
-    let mut path = Vec::new();
-    let mut node = *end;
-    let mut distances = HashMap::with_capacity(valid.len());
-    let mut distance = 0;
-
-    path.push(node);
-    distances.insert(node, distance);
-
-    while node != *start {
-        node = visited[&node];
-        path.push(node);
-        distances.insert(node, distance);
-        distance += 1;
-    }
-
+/// The path from `start` to `end` (both included), and how far each point
+/// on it is from `end`. `None` if there's no way through.
+pub fn search(
+    start: &Point,
+    end: &Point,
+    valid: &Points,
+) -> Option<(Points, HashMap<Point, usize>)> {
+    // Searching back from the end gives every distance to it in one go.
+    let search = search::bfs(*end, |point| neighbors_of(point, valid));
+    let mut path = search.path(start)?;
     path.reverse();
-    (path, distances)
+
+    let distances = path
+        .iter()
+        .map(|point| (*point, search.distances[point] as usize))
+        .collect();
+    Some((path, distances))
 }
 
 pub fn find_cheats(
@@ -103,16 +86,10 @@ pub fn find_cheats(
         4
 
     */
-    let offsets = [
-        (-2, 0),
-        (-1, 1),
-        (0, 2),
-        (1, 1),
-        (2, 0),
-        (1, -1),
-        (0, -2),
-        (-1, -1),
-    ];
+    let offsets: Vec<Vector> = (-2..=2)
+        .flat_map(|row| (-2..=2).map(move |col| Vector::new(row, col)))
+        .filter(|v| v.manhattan() == 2)
+        .collect();
 
     let mut result = HashMap::new();
 
@@ -121,12 +98,9 @@ pub fn find_cheats(
 
     for point in solution.iter() {
         for offset in offsets.iter() {
-            let cheat: (Option<usize>, Option<usize>) = (
-                point.0.checked_add_signed(offset.0),
-                point.1.checked_add_signed(offset.1),
-            );
+            let cheat = utils::Point::from(*point).checked_add(*offset);
 
-            if let (Some(row), Some(col)) = cheat {
+            if let Some(utils::Point { row, col }) = cheat {
                 if solution.contains(&(row, col)) {
                     // eprintln!("row={row}, col={col}, point={point:?}, offset={offset:?}, cheat={cheat:?}");
                     let new_distance = distances.get(&(row, col)).unwrap();
@@ -145,11 +119,29 @@ pub fn find_cheats(
     result
 }
 
+pub fn count_cheats(valid: &Points, start: &Point, end: &Point, min_saving: usize) -> usize {
+    let (path, distances) = search(start, end, valid).expect("No way to the end.");
+    let cheats = find_cheats(&path, &distances);
+    cheats.values().filter(|x| **x >= min_saving).count()
+}
+
 pub fn main(input: &str) -> usize {
     let (valid, start, end) = parse_input(input);
-    let (path, distances) = search(&start, &end, &valid);
-    let cheats = find_cheats(&path, &distances);
-    cheats.values().filter(|x| **x >= 100).count()
+    count_cheats(&valid, &start, &end, 100)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (Points, Point, Point);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1((valid, start, end): &Self::Input) -> Option<Answer> {
+        Some(count_cheats(valid, start, end, 100).into())
+    }
 }
 
 #[cfg(test)]
@@ -174,9 +166,17 @@ mod tests {
     #[test]
     fn test_example_1() {
         let (valid, start, end) = super::parse_input(INPUT);
-        let (path, distances) = super::search(&start, &end, &valid);
+        let (path, distances) = super::search(&start, &end, &valid).unwrap();
         assert_eq!(path[..4], vec![(3, 1), (2, 1), (1, 1), (1, 2)]);
-        assert_eq!(distances.get(&(3, 1)), Some(83).as_ref());
+        assert_eq!(distances.get(&(3, 1)), Some(84).as_ref());
+        assert_eq!(distances.get(&end), Some(0).as_ref());
+        // Each point's distance is the number of steps left, so the start is
+        // a whole path away (this used to be one short next to the end).
+        assert_eq!(distances[&start], path.len() - 1);
+        assert!(path
+            .iter()
+            .enumerate()
+            .all(|(i, p)| distances[p] == path.len() - 1 - i));
 
         let cheats = super::find_cheats(&path, &distances);
         assert_eq!(cheats.get(&((1, 7), (1, 9))), Some(&12));
@@ -14,6 +14,11 @@ How do we do this? Some options:
 
 */
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{Direction, ParseError, Source},
+};
+
 /*
 A DPad.
 
@@ -25,10 +30,7 @@ A DPad.
 */
 pub enum DPad {
     A,
-    U,
-    D,
-    L,
-    R,
+    Move(Direction),
 }
 
 /*
@@ -168,77 +170,77 @@ impl NumPad {
         // match (self, to) {
         //     // A->*
         //     (NumPad::A, NumPad::_0) => {
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_3) => {
-        //         result.push(DPad::U);
+        //         result.push(DPad::Move(Direction::N));
         //     }
         //     (NumPad::A, NumPad::_2) => {
         //         result.extend(self.move_(&NumPad::_3));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_1) => {
         //         result.extend(self.move_(&NumPad::_2));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_6) => {
         //         result.extend(self.move_(&NumPad::_3));
-        //         result.push(DPad::U);
+        //         result.push(DPad::Move(Direction::N));
         //     }
         //     (NumPad::A, NumPad::_5) => {
         //         result.extend(self.move_(&NumPad::_6));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_4) => {
         //         result.extend(self.move_(&NumPad::_5));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_9) => {
         //         result.extend(self.move_(&NumPad::_6));
-        //         result.push(DPad::U);
+        //         result.push(DPad::Move(Direction::N));
         //     }
         //     (NumPad::A, NumPad::_8) => {
         //         result.extend(self.move_(&NumPad::_9));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::A, NumPad::_7) => {
         //         result.extend(self.move_(&NumPad::_9));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     // B->*
         //     (NumPad::_0, NumPad::_2) => {
-        //         result.push(DPad::U);
+        //         result.push(DPad::Move(Direction::N));
         //     }
         //     (NumPad::_0, NumPad::_1) => {
         //         result.extend(self.move_(&NumPad::_2));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::_0, NumPad::_3) => {
         //         result.extend(self.move_(&NumPad::_2));
-        //         result.push(DPad::R);
+        //         result.push(DPad::Move(Direction::E));
         //     }
         //     (NumPad::_0, NumPad::_5) => {
-        //         result.push(DPad::U);
-        //         result.push(DPad::U);
+        //         result.push(DPad::Move(Direction::N));
+        //         result.push(DPad::Move(Direction::N));
         //     }
         //     (NumPad::_0, NumPad::_4) => {
         //         result.extend(self.move_(&NumPad::_5));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::_0, NumPad::_6) => {
         //         result.extend(self.move_(&NumPad::_5));
-        //         result.push(DPad::R);
+        //         result.push(DPad::Move(Direction::E));
         //     }
         //     (NumPad::_0, NumPad::_8) => {
-        //         result.extend([DPad::U, DPad::U, DPad::U]);
+        //         result.extend([DPad::Move(Direction::N), DPad::Move(Direction::N), DPad::Move(Direction::N)]);
         //     }
         //     (NumPad::_0, NumPad::_7) => {
         //         result.extend(self.move_(&NumPad::_8));
-        //         result.push(DPad::L);
+        //         result.push(DPad::Move(Direction::W));
         //     }
         //     (NumPad::_0, NumPad::_9) => {
         //         result.extend(self.move_(&NumPad::_8));
-        //         result.push(DPad::R);
+        //         result.push(DPad::Move(Direction::E));
 
         //     }
 
@@ -285,6 +287,26 @@ pub fn main(_input: &str) -> u64 {
     todo!()
 }
 
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        let source = Source::new(21, input);
+        for line in input.lines() {
+            if let Some(i) = line.find(|c: char| !(c.is_ascii_digit() || c == 'A')) {
+                return Err(source.unexpected_char(line, i));
+            }
+        }
+        Ok(input.to_string())
+    }
+
+    fn part1(input: &String) -> Option<Answer> {
+        Some(main(input).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,7 +1,17 @@
 use std::ops::BitXor;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub fn parse_input(input: &str) -> Vec<u64> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<u64>, ParseError> {
+    let source = Source::new(22, input);
+    input.lines().map(|line| source.number(line)).collect()
 }
 
 pub fn simulate(number: u64, n_iterations: usize) -> u64 {
@@ -22,14 +32,31 @@ pub fn simulate(number: u64, n_iterations: usize) -> u64 {
     new_number
 }
 
-pub fn main(input: &str) -> u64 {
-    let numbers = parse_input(input);
-    let result = numbers
+pub fn sum_secrets(numbers: &[u64]) -> u64 {
+    numbers
         .iter()
         .map(|&n| simulate(n, 2000))
         // .inspect(|x| eprintln!("{x}"))
-        .sum();
-    result
+        .sum()
+}
+
+pub fn main(input: &str) -> u64 {
+    let numbers = parse_input(input);
+    sum_secrets(&numbers)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Vec<u64>, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Vec<u64>) -> Option<Answer> {
+        Some(sum_secrets(input).into())
+    }
 }
 
 #[cfg(test)]
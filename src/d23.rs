@@ -2,14 +2,22 @@ use std::collections::HashMap;
 
 use itertools::Itertools;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 pub fn parse_input(input: &str) -> Vec<(String, String)> {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<(String, String)>, ParseError> {
+    let source = Source::new(23, input);
     input
         .lines()
         .map(|line| {
-            let mut parts = line.split("-");
-            let a = parts.next().unwrap().to_string();
-            let b = parts.next().unwrap().to_string();
-            (a, b)
+            let (a, b) = source.split_once(line, "-")?;
+            Ok((a.to_string(), b.to_string()))
         })
         .collect()
 }
@@ -56,11 +64,7 @@ pub fn connect_nodes(pairs: Vec<(String, String)>) -> Vec<Vec<String>> {
     result
 }
 
-pub fn main(input: &str) -> u64 {
-    let pairs = parse_input(input);
-    // eprintln!("{pairs:?}");
-    let networks = connect_nodes(pairs);
-    // eprintln!("{networks:#?}");
+pub fn count_t_networks(networks: &[Vec<String>]) -> u64 {
     let t_networks: Vec<_> = networks
         .iter()
         .filter(|network| network.iter().filter(|node| node.starts_with("t")).count() > 0)
@@ -68,6 +72,28 @@ pub fn main(input: &str) -> u64 {
 
     t_networks.len() as u64
 }
+
+pub fn main(input: &str) -> u64 {
+    let pairs = parse_input(input);
+    // eprintln!("{pairs:?}");
+    let networks = connect_nodes(pairs);
+    // eprintln!("{networks:#?}");
+    count_t_networks(&networks)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<(String, String)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Option<Answer> {
+        Some(count_t_networks(&connect_nodes(input.clone())).into())
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2,6 +2,11 @@ use std::collections::{HashMap, VecDeque};
 
 use itertools::Itertools;
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 /*
 Combine bits for wires starting with `z`.
 */
@@ -16,6 +21,11 @@ pub enum Op {
 }
 
 pub fn parse_input(input: &str) -> (HashMap<String, u8>, Vec<Operation>) {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<(HashMap<String, u8>, Vec<Operation>), ParseError> {
+    let source = Source::new(24, input);
     let mut initial: HashMap<String, u8> = HashMap::new();
     let mut operations: Vec<_> = Vec::new();
     let mut initial_lines = true;
@@ -26,28 +36,30 @@ pub fn parse_input(input: &str) -> (HashMap<String, u8>, Vec<Operation>) {
             continue;
         }
         if initial_lines {
-            let (key, value) = line.split_once(": ").unwrap();
-            initial.insert(key.to_string(), value.parse().unwrap());
+            let (key, value) = source.split_once(line, ": ")?;
+            initial.insert(key.to_string(), source.number(value)?);
         } else {
-            let (inputs_raw, output) = line.split_once(" -> ").unwrap();
+            let (inputs_raw, output) = source.split_once(line, " -> ")?;
             let inputs = inputs_raw.split_whitespace().collect::<Vec<_>>();
-            assert_eq!(inputs.len(), 3);
+            if inputs.len() != 3 {
+                return Err(source.error(inputs_raw, "expected 'wire OP wire'"));
+            }
             let a = inputs[0].to_string();
             let b = inputs[2].to_string();
             let op = match inputs[1] {
                 "AND" => Op::AND,
                 "OR" => Op::OR,
                 "XOR" => Op::XOR,
-                _ => panic!("Unknown operation"),
+                other => return Err(source.error(other, "expected AND, OR or XOR")),
             };
 
             operations.push((a, op, b, output.to_string()));
         }
     }
-    (initial, operations)
+    Ok((initial, operations))
 }
 
-pub fn run(initial: &HashMap<String, u8>, operations: &Vec<Operation>) -> HashMap<String, u8> {
+pub fn run(initial: &HashMap<String, u8>, operations: &[Operation]) -> HashMap<String, u8> {
     // We need to figure out which operations are ready to be run (i.e. both
     // their inputs are known)
 
@@ -116,9 +128,8 @@ pub fn run(initial: &HashMap<String, u8>, operations: &Vec<Operation>) -> HashMa
     ready
 }
 
-pub fn main(input: &str) -> u64 {
-    let (initial, operations) = parse_input(input);
-    let result = run(&initial, &operations);
+pub fn z_value(initial: &HashMap<String, u8>, operations: &[Operation]) -> u64 {
+    let result = run(initial, operations);
     let zs: String = result
         .iter()
         .filter(|(k, _)| k.starts_with("z"))
@@ -129,6 +140,25 @@ pub fn main(input: &str) -> u64 {
     u64::from_str_radix(&zs, 2).unwrap()
 }
 
+pub fn main(input: &str) -> u64 {
+    let (initial, operations) = parse_input(input);
+    z_value(&initial, &operations)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (HashMap<String, u8>, Vec<Operation>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1((initial, operations): &Self::Input) -> Option<Answer> {
+        Some(z_value(initial, operations).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
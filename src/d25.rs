@@ -4,18 +4,38 @@ Locks go down. Keys go up.
 
 */
 
+use crate::{
+    solution::{Answer, Solution},
+    utils::{ParseError, Source},
+};
+
 type Lock = Vec<u8>;
 type Key = Vec<u8>;
 
 pub fn parse_input(input: &str) -> (Vec<Lock>, Vec<Key>) {
+    try_parse_input(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn try_parse_input(input: &str) -> Result<(Vec<Lock>, Vec<Key>), ParseError> {
+    let source = Source::new(25, input);
     let mut locks = Vec::new();
     let mut keys = Vec::new();
 
-    input.split("\n\n").for_each(|group| {
+    for group in source.blocks(input) {
+        source.check_rectangular(group)?;
+        if group.lines().count() != 7 {
+            return Err(source.error(group, "expected 7 rows"));
+        }
+        for line in group.lines() {
+            if let Some(i) = line.find(|c| c != '#' && c != '.') {
+                return Err(source.unexpected_char(line, i));
+            }
+        }
+
         let is_lock = group.starts_with("#");
 
         if is_lock {
-            let mut lock = vec![0; group.split_once("\n").unwrap().0.len()];
+            let mut lock = vec![0; group.lines().next().unwrap().len()];
 
             for line in group.lines().skip(1) {
                 for (i, c) in line.chars().enumerate() {
@@ -26,7 +46,7 @@ pub fn parse_input(input: &str) -> (Vec<Lock>, Vec<Key>) {
             }
             locks.push(lock);
         } else {
-            let mut key = vec![5; group.split_once("\n").unwrap().0.len()];
+            let mut key = vec![5; group.lines().next().unwrap().len()];
 
             for line in group.lines().skip(1) {
                 for (i, c) in line.chars().enumerate() {
@@ -43,13 +63,12 @@ pub fn parse_input(input: &str) -> (Vec<Lock>, Vec<Key>) {
 
         // locks.push(lock);
         // keys.push(key);
-    });
+    }
 
-    (locks, keys)
+    Ok((locks, keys))
 }
 
-pub fn main(input: &str) -> usize {
-    let (locks, keys) = parse_input(input);
+pub fn count_fits(locks: &[Lock], keys: &[Key]) -> usize {
     let mut result = 0;
 
     for lock in locks.iter() {
@@ -63,6 +82,25 @@ pub fn main(input: &str) -> usize {
     result
 }
 
+pub fn main(input: &str) -> usize {
+    let (locks, keys) = parse_input(input);
+    count_fits(&locks, &keys)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (Vec<Lock>, Vec<Key>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_input(input)
+    }
+
+    fn part1((locks, keys): &Self::Input) -> Option<Answer> {
+        Some(count_fits(locks, keys).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +151,9 @@ mod tests {
         let (locks, keys) = parse_input(INPUT);
         assert_eq!(locks[0], vec![0, 5, 3, 4, 3]);
         assert_eq!(keys[0], vec![5, 0, 2, 1, 3]);
+
+        let crlf = INPUT.replace('\n', "\r\n");
+        assert_eq!(parse_input(&crlf), (locks, keys));
     }
 
     #[test]
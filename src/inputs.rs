@@ -0,0 +1,59 @@
+/*
+Where puzzle inputs live.
+
+By convention day N's input is `inputs/NN.txt`. The directory can be
+overridden, an explicit path always wins, and `-` reads from stdin.
+*/
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+pub fn path(dir: &Path, day: u8) -> PathBuf {
+    dir.join(format!("{day:02}.txt"))
+}
+
+/// The path `read` would use, for messages and reports. `-` means stdin.
+pub fn resolve(input: Option<&Path>, dir: &Path, day: u8) -> PathBuf {
+    match input {
+        Some(input) => input.to_path_buf(),
+        None => path(dir, day),
+    }
+}
+
+pub fn read(input: Option<&Path>, dir: &Path, day: u8) -> io::Result<String> {
+    let path = resolve(input, dir, day);
+    if path == Path::new("-") {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        Ok(buf)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{path, resolve};
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            path(Path::new("inputs"), 1),
+            PathBuf::from("inputs").join("01.txt")
+        );
+        assert_eq!(
+            resolve(None, Path::new("other"), 21),
+            PathBuf::from("other").join("21.txt")
+        );
+        assert_eq!(
+            resolve(Some(Path::new("-")), Path::new("inputs"), 21),
+            PathBuf::from("-")
+        );
+    }
+}
@@ -24,4 +24,14 @@ pub mod d23;
 pub mod d24;
 pub mod d25;
 
+pub mod answers;
+pub mod client;
+pub mod inputs;
+pub mod recorder;
+pub mod render;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
 pub mod utils;
@@ -1,336 +1,818 @@
 use aoc_rs_24::{
-    d01, d02, d03, d04, d05, d06, d07, d08, d09, d10, d11, d12, d13, d14, d15, d16, d17, d18, d19,
-    d20, d22, d23, d24, d25,
+    answers::{Answers, Verdict},
+    client::{self, Client, Outcome},
+    d06, d07, d11, d13, d14, d15, d18, inputs,
+    recorder::{self, Mode, Recorder},
+    render::{self, Style},
+    report::{self, Format, Row},
+    runner::{self, Record, Status},
+    scaffold,
+    solution::{self, Answer, Part},
+    utils::Grid,
+};
+use clap::{error::ErrorKind, Args, CommandFactory, Parser, Subcommand};
+use std::{
+    fs, io, panic,
+    path::{Path, PathBuf},
+    process::{self, ExitCode},
 };
-use clap::{Parser, Subcommand};
-use std::{fs, path::PathBuf};
 
 #[derive(Parser)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, arg_required_else_help = true)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Day to run, as an alternative to the `dN` subcommands
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: Option<u8>,
+
+    /// Part to run with `--day`
+    #[arg(short, long, requires = "day")]
+    part: Option<Part>,
+
+    /// Puzzle input to use with `--day`; `-` reads stdin
+    #[arg(short, long, requires = "day")]
+    input: Option<PathBuf>,
+
+    /// Where to look for `NN.txt` inputs when no `--input` is given
+    #[arg(long, global = true, default_value = inputs::DEFAULT_DIR)]
+    inputs_dir: PathBuf,
+
+    /// Known answers to check results against
+    #[arg(long, global = true, default_value = "answers.txt")]
+    answers: PathBuf,
+
+    /// How to print results
+    #[arg(long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Args)]
+struct DayArgs {
+    /// Puzzle input; defaults to `NN.txt` in `--inputs-dir`, `-` reads stdin
+    #[arg(short, long)]
+    input: Option<PathBuf>,
+
+    /// Which part to run
+    #[arg(short, long, default_value = "1")]
+    part: Part,
+}
+
+impl DayArgs {
+    /// Folds in a day's old part 2 flag (`--damped`, `--as-score`, ...).
+    fn part_or(&self, part_two: bool) -> Part {
+        if part_two {
+            Part::Two
+        } else {
+            self.part
+        }
+    }
+}
+
+/// For days that simulate step by step.
+#[derive(Args)]
+struct RecordArgs {
+    /// Play part 1's steps back in the terminal
+    #[arg(long)]
+    play: bool,
+
+    /// Frames per second for `--play` and animations, from 0.1 to 1000
+    #[arg(long, default_value_t = 10.0, value_parser = parse_fps)]
+    fps: f64,
+
+    /// Save part 1's steps as an animated `.png`, or else one PNG per step in
+    /// this directory
+    #[arg(long)]
+    record: Option<PathBuf>,
+}
+
+fn parse_fps(text: &str) -> Result<f64, String> {
+    let fps: f64 = text.parse().map_err(|_| format!("{text} isn't a number"))?;
+    if recorder::FPS.contains(&fps) {
+        Ok(fps)
+    } else {
+        Err(format!(
+            "should be between {} and {}",
+            recorder::FPS.start(),
+            recorder::FPS.end()
+        ))
+    }
+}
+
+impl RecordArgs {
+    fn is_on(&self) -> bool {
+        self.play || self.record.is_some()
+    }
+}
+
+#[derive(Args)]
+struct ClientArgs {
+    /// Where the puzzle site lives
+    #[arg(long, default_value = client::DEFAULT_BASE_URL)]
+    base_url: String,
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    /// does testing things
     D1 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         as_score: bool,
     },
-
     D2 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         damped: bool,
     },
-
     D3 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         switches: bool,
     },
-
     D4 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         xs: bool,
     },
-
     D5 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         fix_only: bool,
     },
-
     D6 {
-        #[arg(short, long)]
-        input: PathBuf,
-    },
+        #[command(flatten)]
+        args: DayArgs,
+
+        #[command(flatten)]
+        record: RecordArgs,
 
+        /// Write part 1's walk, with where the guard turned and why it
+        /// stopped, to this file as JSON
+        #[arg(long)]
+        trace: Option<PathBuf>,
+    },
     D7 {
+        #[command(flatten)]
+        args: DayArgs,
+
+        /// Operators to try instead of the part's, comma separated
+        #[arg(short, long, value_delimiter = ',')]
+        operators: Option<Vec<d07::Operator>>,
+
+        /// Give `*` and `/` precedence over `+` and `-`, rather than going left
+        /// to right
+        #[arg(long)]
+        precedence: bool,
+
+        /// Check lines on this many threads at once (needs the `parallel`
+        /// feature)
         #[arg(short, long)]
-        input: PathBuf,
+        threads: Option<usize>,
     },
-
     D8 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
-
     D9 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D10 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, action)]
+        /// Same as `--part 2`
+        #[arg(short, long, action, hide = true, conflicts_with = "part")]
         as_rating: bool,
     },
-
     D11 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, default_value_t = 25)]
-        n_blinks: u64,
+        /// Blink this many times instead of the part's count (25 or 75)
+        #[arg(short, long)]
+        n_blinks: Option<u64>,
     },
     D12 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D13 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, default_value_t = 0)]
-        offset: u64,
+        /// Move the prizes by this much instead of part 2's offset
+        #[arg(short, long)]
+        offset: Option<u64>,
     },
     D14 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(short, long, default_value_t = 100)]
+        #[arg(short, long, default_value_t = d14::N_ITER)]
         n_iter: usize,
 
-        #[arg(short, long, default_value_t = 101)]
+        #[arg(short, long, default_value_t = d14::WIDTH)]
         width: usize,
 
-        #[arg(long, default_value_t = 103)]
+        #[arg(long, default_value_t = d14::HEIGHT)]
         height: usize,
-    },
 
-    D15 {
-        #[arg(short, long)]
-        input: PathBuf,
+        /// Draw the robots at the end to a PNG, PPM, ANSI (`.ans`) or text file
+        #[arg(long)]
+        render: Option<PathBuf>,
+
+        #[command(flatten)]
+        record: RecordArgs,
     },
+    D15 {
+        #[command(flatten)]
+        args: DayArgs,
 
+        /// Draw the warehouse after part 1's moves to a PNG, PPM, ANSI (`.ans`)
+        /// or text file
+        #[arg(long)]
+        render: Option<PathBuf>,
+
+        #[command(flatten)]
+        record: RecordArgs,
+    },
     D16 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
-
     D17 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D18 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
 
-        #[arg(long, default_value_t = 71)]
+        #[arg(long, default_value_t = d18::SIZE)]
         n_rows: usize,
 
-        #[arg(long, default_value_t = 71)]
+        #[arg(long, default_value_t = d18::SIZE)]
         n_cols: usize,
 
-        #[arg(long, default_value_t = 1024)]
+        #[arg(long, default_value_t = d18::N_STEPS)]
         n_steps: usize,
     },
-
     D19 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D20 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
+    },
+    D21 {
+        #[command(flatten)]
+        args: DayArgs,
     },
     D22 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D23 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D24 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
     },
     D25 {
-        #[arg(short, long)]
-        input: PathBuf,
+        #[command(flatten)]
+        args: DayArgs,
+    },
+
+    /// Run every day against its input and print a timing table
+    RunAll,
+
+    /// Download a day's input into `--inputs-dir`, unless it's already there
+    Fetch {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+
+    /// Submit an answer, solving the part first if no answer is given
+    Submit {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        #[arg(short, long, default_value = "1")]
+        part: Part,
+
+        answer: Option<String>,
+
+        #[command(flatten)]
+        client: ClientArgs,
+    },
+
+    /// Start a new day: write `src/dNN.rs` and register it in the library and CLI
+    NewDay {
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+
+        /// The crate to add the day to
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
     },
 }
 
-fn main() {
-    let cli = Cli::parse();
+fn exit_code(failed: bool) -> ExitCode {
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
 
-    match &cli.command {
-        Some(Commands::D1 { input, as_score }) => {
-            let data = d01::parse_input(&fs::read_to_string(input).expect("Failed to read file."));
-            if *as_score {
-                let result = d01::similarity_score(&data);
-                println!("{result}"); // 1830467
-            } else {
-                let result = d01::find_distance(&data);
-                println!("{result}"); // 26674158
+fn print_records(format: Format, records: &[(Record, PathBuf)]) {
+    match format {
+        Format::Text => {
+            let records: Vec<_> = records.iter().map(|(r, _)| r.clone()).collect();
+            print!("{}", runner::format_table(&records));
+        }
+        Format::Json => {
+            for (record, input) in records {
+                println!("{}", Row::new(record, input).to_json());
             }
         }
-        Some(Commands::D2 { input, damped }) => {
-            let data = d02::parse_input(&fs::read_to_string(input).expect("Failed to read file."));
-            let result = d02::count_safe(&data, *damped);
-            println!("{result}"); // 442, 493
+        Format::Csv => {
+            println!("{}", report::CSV_HEADER);
+            for (record, input) in records {
+                println!("{}", Row::new(record, input).to_csv());
+            }
         }
-        Some(Commands::D3 { input, switches }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
+    }
+}
 
-            let result = if *switches {
-                d03::evaluate_switches(&input) // 84893551 is too high
-            } else {
-                d03::evaluate(&input) // 160672468
-            };
-            println!("{result}"); //
-        }
-        Some(Commands::D4 { input, xs }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d04::count_xmas(&input, *xs);
-            // 2454 and 1858
-            println!("{result}");
-        }
-        Some(Commands::D5 { input, fix_only }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d05::check(&input, fix_only);
-            println!("{result}");
-        }
-        Some(Commands::D6 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d06::count_positions(&input);
-            println!("{result}");
-        }
-        Some(Commands::D7 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d07::total_calibration_result(&input);
-            // part 1: 20281182715321 in ~1s
-            // part 2: 159490400628354 (in 22s)
-            println!("{result}");
-        }
+/// For `--render`. Panics so a failure shows up like any other in the run.
+fn save_render<T>(path: &Path, grid: &Grid<T>, palette: impl Fn(&T) -> Style) {
+    render::save(path, grid, palette, render::SCALE)
+        .unwrap_or_else(|err| panic!("Failed to write {}: {err}", path.display()));
+}
 
-        Some(Commands::D8 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d08::count_antinodes(&input);
-            println!("{result}");
-        }
+/// For `--play` and `--record`. Plays to stderr so the answer still goes to
+/// stdout, and panics like `save_render`.
+fn replay<T: Clone + PartialEq>(
+    args: &RecordArgs,
+    recorder: &Recorder<T>,
+    palette: impl Fn(&T) -> Style,
+) {
+    if args.play {
+        recorder
+            .play(&mut io::stderr(), &palette, args.fps)
+            .unwrap_or_else(|err| panic!("Failed to play: {err}"));
+    }
+    if let Some(path) = &args.record {
+        let saved = if path.extension().is_some_and(|ext| ext == "png") {
+            recorder.save_animation(path, &palette, render::SCALE, args.fps)
+        } else {
+            recorder
+                .save_images(path, &palette, render::SCALE)
+                .map(|_| ())
+        };
+        saved.unwrap_or_else(|err| panic!("Failed to write {}: {err}", path.display()));
+    }
+}
 
-        Some(Commands::D9 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d09::run(&input);
-            // 6448989155953
-            println!("{result}");
-        }
+/// Loads the answers file, exiting with where it went wrong if it's malformed.
+fn load_answers(cli: &Cli) -> Answers {
+    Answers::load(&cli.answers).unwrap_or_else(|err| {
+        eprintln!("Invalid answers file {}, {err}", cli.answers.display());
+        process::exit(1)
+    })
+}
 
-        Some(Commands::D10 { input, as_rating }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d10::main(&input, *as_rating);
-            // 531
-            println!("{result}");
-        }
-        Some(Commands::D11 { input, n_blinks }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d11::main(&input, *n_blinks);
-            // 531
-            println!("{result}");
-        }
-        Some(Commands::D12 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d12::main(&input);
-            println!("{result}");
-        }
-        Some(Commands::D13 { input, offset }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d13::main(&input, *offset);
-            // 531
-            println!("{result}");
-        }
+/// The answers are for the real input with each day's usual parameters, so
+/// only check against them when that's what ran.
+fn check_if(mut record: Record, cli: &Cli, is_default: bool) -> Record {
+    if is_default {
+        record.check(&load_answers(cli));
+    }
+    record
+}
 
-        Some(Commands::D14 {
-            input,
-            n_iter,
-            width,
-            height,
-        }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d14::main(&input, *n_iter, *width, *height);
-            // 531
-            println!("{result}");
-        }
+/// Reads the input for a single day, runs it with `run` and reports the result.
+fn run_single(
+    cli: &Cli,
+    day: u8,
+    part: Part,
+    input: Option<&Path>,
+    run: impl FnOnce(&str) -> Record,
+) -> ExitCode {
+    if cli.format != Format::Text {
+        // Failures are reported in the record itself.
+        panic::set_hook(Box::new(|_| {}));
+    }
 
-        Some(Commands::D15 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d15::main(&input);
-            println!("{result}");
+    let path = inputs::resolve(input, &cli.inputs_dir, day);
+    let record = match inputs::read(input, &cli.inputs_dir, day) {
+        Ok(input) => run(&input),
+        Err(err) if cli.format == Format::Text => {
+            eprintln!("Failed to read {}: {err}", path.display());
+            return ExitCode::FAILURE;
         }
+        Err(_) => Record::missing_input(day, part),
+    };
 
-        Some(Commands::D16 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d16::main(&input);
-            println!("{result}");
-        }
-        Some(Commands::D17 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d17::main(&input);
-            println!("{result}");
-        }
+    if cli.format != Format::Text {
+        print_records(cli.format, &[(record.clone(), path)]);
+        return exit_code(record.is_failure());
+    }
 
-        Some(Commands::D18 {
-            input,
-            n_rows,
-            n_cols,
-            n_steps,
-        }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d18::main(&input, *n_rows, *n_cols, *n_steps);
-            println!("{result}");
+    match &record.status {
+        Status::Solved(answer) => {
+            println!("{answer}");
+            match &record.verdict {
+                Verdict::Unknown => {}
+                Verdict::Fail { expected } => eprintln!("{} (expected {expected})", record.verdict),
+                verdict => eprintln!("{verdict}"),
+            }
         }
+        Status::NotImplemented => eprintln!("Day {day} part {part} is not implemented."),
+        Status::Panicked(message) => eprintln!("Day {day} part {part} failed: {message}"),
+        Status::Invalid(error) => eprintln!("Invalid input {}: {error}", path.display()),
+        Status::MissingInput => eprintln!("Missing input {}", path.display()),
+    }
+    exit_code(record.is_failure())
+}
 
-        Some(Commands::D19 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d19::main(&input);
-            println!("{result}");
+fn fetch(cli: &Cli, day: u8, args: &ClientArgs) -> ExitCode {
+    let fetched = Client::from_env(&args.base_url, &cli.inputs_dir)
+        .and_then(|client| client.fetch_input(day));
+    match fetched {
+        Ok(_) => {
+            println!("{}", inputs::path(&cli.inputs_dir, day).display());
+            ExitCode::SUCCESS
         }
-
-        Some(Commands::D20 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d20::main(&input);
-            println!("{result}");
+        Err(err) => {
+            eprintln!("Failed to fetch day {day}: {err}");
+            ExitCode::FAILURE
         }
+    }
+}
 
-        Some(Commands::D22 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d22::main(&input);
-            println!("{result}");
+fn submit(cli: &Cli, day: u8, part: Part, answer: Option<&str>, args: &ClientArgs) -> ExitCode {
+    let client = match Client::from_env(&args.base_url, &cli.inputs_dir) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
         }
-        Some(Commands::D23 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d23::main(&input);
-            println!("{result}");
+    };
+
+    let answer = match answer {
+        Some(answer) => Answer::Text(answer.to_string()),
+        None => {
+            let record = match client.fetch_input(day) {
+                Ok(input) => runner::run_part(solution::get(day).unwrap(), &input, part),
+                Err(err) => {
+                    eprintln!("Failed to fetch day {day}: {err}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            match record.status {
+                Status::Solved(answer) => answer,
+                status => {
+                    eprintln!("Day {day} part {part}: {status}");
+                    return ExitCode::FAILURE;
+                }
+            }
         }
-        Some(Commands::D24 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d24::main(&input);
-            println!("{result}");
+    };
+
+    // Don't spend a submission on something we already know about.
+    match load_answers(cli).check(day, part, &answer) {
+        Verdict::Pass => {
+            eprintln!("{answer} is already known to be right.");
+            return ExitCode::SUCCESS;
+        }
+        Verdict::KnownWrong => {
+            eprintln!("{answer} is already known to be wrong.");
+            return ExitCode::FAILURE;
         }
-        Some(Commands::D25 { input }) => {
-            let input = fs::read_to_string(input).expect("Failed to read file.");
-            let result = d25::main(&input);
-            println!("{result}");
+        Verdict::Fail { expected } => {
+            eprintln!("{answer} is wrong, the answer is already known to be {expected}.");
+            return ExitCode::FAILURE;
         }
+        Verdict::Unknown => {}
+    }
 
-        None => {}
+    let outcome = match client.submit(day, part, &answer) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("Failed to submit: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("{outcome}");
+
+    let correct = match &outcome {
+        Outcome::Correct => true,
+        Outcome::Incorrect(_) => false,
+        _ => return ExitCode::FAILURE,
+    };
+    if let Err(err) = Answers::record(&cli.answers, day, part, &answer, correct) {
+        eprintln!(
+            "Failed to record answer in {}: {err}",
+            cli.answers.display()
+        );
     }
+    exit_code(!correct)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let (day, part, input) = match (&cli.command, cli.day) {
+        (Some(_), Some(_)) => Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "--day can't be combined with a subcommand",
+            )
+            .exit(),
+        (None, Some(day)) => (day, cli.part.unwrap_or(Part::One), &cli.input),
+        (None, None) => {
+            Cli::command().print_help().expect("Failed to print help.");
+            return ExitCode::SUCCESS;
+        }
+        (Some(command), None) => match command {
+            Commands::D1 { args, as_score } => (1, args.part_or(*as_score), &args.input),
+            Commands::D2 { args, damped } => (2, args.part_or(*damped), &args.input),
+            Commands::D3 { args, switches } => (3, args.part_or(*switches), &args.input),
+            Commands::D4 { args, xs } => (4, args.part_or(*xs), &args.input),
+            Commands::D5 { args, fix_only } => (5, args.part_or(*fix_only), &args.input),
+            Commands::D6 {
+                args,
+                record,
+                trace,
+            } if record.is_on() || trace.is_some() => {
+                if args.part == Part::Two {
+                    Cli::command()
+                        .error(
+                            ErrorKind::ArgumentConflict,
+                            "--trace, --play and --record only apply to part 1",
+                        )
+                        .exit()
+                }
+                return run_single(&cli, 6, Part::One, args.input.as_deref(), |input| {
+                    let parse = || d06::try_parse_input(input);
+                    let record = runner::run_custom(6, Part::One, parse, |mut state| {
+                        if let Some(path) = trace {
+                            let json = state.clone().run_traced().to_json();
+                            fs::write(path, json).unwrap_or_else(|err| {
+                                panic!("Failed to write {}: {err}", path.display())
+                            });
+                        }
+                        let mut recorder = Recorder::new(Mode::Diffs);
+                        state.run_with(|state| {
+                            if record.is_on() {
+                                recorder.record(&state.grid());
+                            }
+                        });
+                        replay(record, &recorder, d06::style);
+                        state.count_visited().into()
+                    });
+                    check_if(record, &cli, args.input.is_none())
+                });
+            }
+            Commands::D6 { args, .. } => (6, args.part, &args.input),
+            Commands::D7 {
+                args,
+                operators,
+                precedence,
+                threads,
+            } if operators.is_some() || *precedence || threads.is_some() => {
+                #[cfg(not(feature = "parallel"))]
+                if threads.is_some() {
+                    Cli::command()
+                        .error(
+                            ErrorKind::ArgumentConflict,
+                            "--threads needs the `parallel` feature",
+                        )
+                        .exit()
+                }
+                let mut rules = d07::Rules::for_part(args.part);
+                if let Some(operators) = operators {
+                    rules.operators = operators.clone();
+                }
+                rules.precedence = *precedence;
+                // Threads don't change the answer.
+                let is_default = args.input.is_none() && rules == d07::Rules::for_part(args.part);
+                return run_single(&cli, 7, args.part, args.input.as_deref(), |input| {
+                    let parse = || d07::try_parse_input(input);
+                    let record = runner::run_custom(7, args.part, parse, |lines| {
+                        #[cfg(feature = "parallel")]
+                        let total = d07::sum_valid_lines_parallel(&lines, &rules, *threads);
+                        #[cfg(not(feature = "parallel"))]
+                        let total = d07::sum_valid_lines(&lines, &rules);
+                        total.into()
+                    });
+                    check_if(record, &cli, is_default)
+                });
+            }
+            Commands::D7 { args, .. } => (7, args.part, &args.input),
+            Commands::D8 { args } => (8, args.part, &args.input),
+            Commands::D9 { args } => (9, args.part, &args.input),
+            Commands::D10 { args, as_rating } => (10, args.part_or(*as_rating), &args.input),
+            // Days with extra parameters call straight into their modules when
+            // they're given.
+            Commands::D11 {
+                args,
+                n_blinks: Some(n_blinks),
+            } => {
+                return run_single(&cli, 11, args.part, args.input.as_deref(), |input| {
+                    runner::run_custom(
+                        11,
+                        args.part,
+                        || d11::try_parse_input(input),
+                        |stones| d11::count_stones(&stones, *n_blinks).into(),
+                    )
+                });
+            }
+            Commands::D11 { args, .. } => (11, args.part, &args.input),
+            Commands::D12 { args } => (12, args.part, &args.input),
+            Commands::D13 {
+                args,
+                offset: Some(offset),
+            } => {
+                if args.part == Part::One {
+                    Cli::command()
+                        .error(
+                            ErrorKind::ArgumentConflict,
+                            "--offset only applies to part 2",
+                        )
+                        .exit()
+                }
+                return run_single(&cli, 13, args.part, args.input.as_deref(), |input| {
+                    runner::run_custom(
+                        13,
+                        args.part,
+                        || d13::try_parse_input(input),
+                        |games| d13::total_cost_with_offset(&games, *offset).into(),
+                    )
+                });
+            }
+            Commands::D13 { args, .. } => (13, args.part, &args.input),
+            Commands::D14 {
+                args,
+                n_iter,
+                width,
+                height,
+                render,
+                record,
+            } => {
+                let is_default = args.input.is_none()
+                    && (*width, *height) == (d14::WIDTH, d14::HEIGHT)
+                    && (args.part == Part::Two || *n_iter == d14::N_ITER);
+                return run_single(&cli, 14, args.part, args.input.as_deref(), |input| {
+                    let parse = || d14::try_parse_input(input);
+                    let record = runner::run_custom(14, args.part, parse, |state| {
+                        let (answer, steps) = match args.part {
+                            Part::One => (
+                                d14::safety_factor(&state, *n_iter, *width, *height),
+                                *n_iter as u64,
+                            ),
+                            Part::Two => {
+                                let steps = d14::find_tree(&state, *width, *height);
+                                (steps, steps)
+                            }
+                        };
+                        if let Some(path) = render {
+                            let state = d14::step_n(&state, *width, *height, steps as i32);
+                            save_render(path, &d14::robots(&state, *width, *height), d14::style);
+                        }
+                        if record.is_on() {
+                            let mut recorder = Recorder::new(Mode::Diffs);
+                            for grid in d14::frames(&state, *width, *height, steps as usize) {
+                                recorder.record(&grid);
+                            }
+                            replay(record, &recorder, d14::style);
+                        }
+                        answer.into()
+                    });
+                    check_if(record, &cli, is_default)
+                });
+            }
+            Commands::D15 {
+                args,
+                render,
+                record,
+            } if args.part == Part::One && (render.is_some() || record.is_on()) => {
+                return run_single(&cli, 15, Part::One, args.input.as_deref(), |input| {
+                    let parse = || d15::try_parse_input(input);
+                    let record = runner::run_custom(15, Part::One, parse, |mut map| {
+                        if record.is_on() {
+                            let mut recorder = Recorder::new(Mode::Diffs);
+                            map.step_with(|map| recorder.record(&map.grid()));
+                            replay(record, &recorder, d15::Object::style);
+                        } else {
+                            map.step();
+                        }
+                        if let Some(path) = render {
+                            save_render(path, &map.grid(), d15::Object::style);
+                        }
+                        map.score().into()
+                    });
+                    check_if(record, &cli, args.input.is_none())
+                });
+            }
+            Commands::D15 { args, .. } => (15, args.part, &args.input),
+            Commands::D16 { args } => (16, args.part, &args.input),
+            Commands::D17 { args } => (17, args.part, &args.input),
+            Commands::D18 {
+                args,
+                n_rows,
+                n_cols,
+                n_steps,
+            } => {
+                let is_default = args.input.is_none()
+                    && (*n_rows, *n_cols) == (d18::SIZE, d18::SIZE)
+                    && (args.part == Part::Two || *n_steps == d18::N_STEPS);
+                return run_single(&cli, 18, args.part, args.input.as_deref(), |input| {
+                    let parse = || d18::try_parse_input(input, *n_rows, *n_cols);
+                    let record =
+                        runner::run_custom(18, args.part, parse, |mut grid| match args.part {
+                            Part::One => {
+                                let path = grid.run(*n_steps).expect("No way to the exit.");
+                                (path.len() - 1).into()
+                            }
+                            Part::Two => {
+                                let (x, y) =
+                                    grid.first_blocker().expect("The exit is never cut off.");
+                                format!("{x},{y}").into()
+                            }
+                        });
+                    check_if(record, &cli, is_default)
+                });
+            }
+            Commands::D19 { args } => (19, args.part, &args.input),
+            Commands::D20 { args } => (20, args.part, &args.input),
+            Commands::D21 { args } => (21, args.part, &args.input),
+            Commands::D22 { args } => (22, args.part, &args.input),
+            Commands::D23 { args } => (23, args.part, &args.input),
+            Commands::D24 { args } => (24, args.part, &args.input),
+            Commands::D25 { args } => (25, args.part, &args.input),
+
+            Commands::RunAll => {
+                let records: Vec<_> = runner::run_all(&cli.inputs_dir, &load_answers(&cli))
+                    .into_iter()
+                    .map(|r| {
+                        let input = inputs::path(&cli.inputs_dir, r.day);
+                        (r, input)
+                    })
+                    .collect();
+                print_records(cli.format, &records);
+                return exit_code(records.iter().any(|(r, _)| r.is_failure()));
+            }
+            Commands::Fetch { day, client } => return fetch(&cli, *day, client),
+            Commands::Submit {
+                day,
+                part,
+                answer,
+                client,
+            } => return submit(&cli, *day, *part, answer.as_deref(), client),
+            Commands::NewDay { day, root } => match scaffold::new_day(root, *day) {
+                Ok(paths) => {
+                    for path in paths {
+                        println!("{}", path.display());
+                    }
+                    return ExitCode::SUCCESS;
+                }
+                Err(err) => {
+                    eprintln!("{err}");
+                    return ExitCode::FAILURE;
+                }
+            },
+        },
+    };
+
+    let day = solution::get(day).expect("Day is not registered.");
+    let is_default = input.is_none();
+    run_single(&cli, day.day, part, input.as_deref(), |input| {
+        check_if(runner::run_part(day, input, part), &cli, is_default)
+    })
 }
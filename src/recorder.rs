@@ -0,0 +1,277 @@
+/*
+Recording step-by-step simulations as grid frames, to watch them afterwards
+instead of sprinkling `eprintln!`s.
+
+A `Recorder` keeps either every frame whole or, to save memory on long runs,
+the first frame and then only the cells that changed each step. Either way
+the frames come back whole, and can be
+
+1. played back in the terminal, as ANSI text at a given speed
+2. saved as a directory of images, one per frame
+3. saved as one animated PNG
+*/
+use std::{
+    fs,
+    io::{self, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    render::{self, Image, Style},
+    utils::{Grid, Point},
+};
+
+/// Frames per second that `play` and `animation` take. The slowest still
+/// fits an APNG frame delay (at most 65535 ms).
+pub const FPS: RangeInclusive<f64> = 0.1..=1000.0;
+
+fn check_fps(fps: f64) {
+    assert!(
+        FPS.contains(&fps),
+        "{fps} frames per second isn't between {} and {}.",
+        FPS.start(),
+        FPS.end()
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Full,
+    Diffs,
+}
+
+#[derive(Debug, Clone)]
+enum Frame<T> {
+    Full(Grid<T>),
+    /// The cells that changed since the previous frame.
+    Diff(Vec<(Point, T)>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Recorder<T> {
+    mode: Mode,
+    frames: Vec<Frame<T>>,
+    /// The last frame recorded, to diff the next one against.
+    last: Option<Grid<T>>,
+}
+
+impl<T: Clone + PartialEq> Recorder<T> {
+    pub fn new(mode: Mode) -> Recorder<T> {
+        Recorder {
+            mode,
+            frames: Vec::new(),
+            last: None,
+        }
+    }
+
+    pub fn record(&mut self, grid: &Grid<T>) {
+        let frame = match (&self.last, self.mode) {
+            (Some(last), Mode::Diffs)
+                if (last.n_rows(), last.n_cols()) == (grid.n_rows(), grid.n_cols()) =>
+            {
+                let changed = grid
+                    .iter()
+                    .zip(last.values())
+                    .filter(|((_, new), old)| new != old)
+                    .map(|(((row, col), new), _)| (Point::new(row, col), new.clone()))
+                    .collect();
+                Frame::Diff(changed)
+            }
+            _ => Frame::Full(grid.clone()),
+        };
+        self.frames.push(frame);
+        self.last = Some(grid.clone());
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Every frame, whole, in the order they were recorded.
+    pub fn frames(&self) -> impl Iterator<Item = Grid<T>> + '_ {
+        let mut current: Option<Grid<T>> = None;
+        self.frames.iter().map(move |frame| {
+            let grid = match (frame, current.take()) {
+                (Frame::Full(grid), _) => grid.clone(),
+                (Frame::Diff(changed), Some(mut grid)) => {
+                    for (p, value) in changed.iter() {
+                        grid[*p] = value.clone();
+                    }
+                    grid
+                }
+                (Frame::Diff(_), None) => unreachable!("The first frame is always full."),
+            };
+            current = Some(grid.clone());
+            grid
+        })
+    }
+
+    /// Draws each frame over the last one, `fps` frames a second (within
+    /// `FPS`).
+    pub fn play(
+        &self,
+        out: &mut impl Write,
+        palette: impl Fn(&T) -> Style,
+        fps: f64,
+    ) -> io::Result<()> {
+        check_fps(fps);
+        let delay = Duration::from_secs_f64(1.0 / fps);
+        for (i, grid) in self.frames().enumerate() {
+            // Clear the screen and go back to the top left.
+            write!(out, "\x1b[2J\x1b[H")?;
+            write!(out, "{}", render::ansi(&grid, &palette))?;
+            writeln!(out, "Frame {} of {}", i + 1, self.len())?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+        Ok(())
+    }
+
+    /// One PNG per frame in `dir` (created if need be), `frame_0000.png`
+    /// onwards. Returns the files written.
+    pub fn save_images(
+        &self,
+        dir: &Path,
+        palette: impl Fn(&T) -> Style,
+        scale: usize,
+    ) -> io::Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir)?;
+        let mut paths = Vec::with_capacity(self.len());
+        for (i, grid) in self.frames().enumerate() {
+            let path = dir.join(format!("frame_{i:04}.png"));
+            fs::write(&path, Image::from_grid(&grid, &palette, scale).png())?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Every frame in one animated PNG, playing once at `fps` frames a
+    /// second (within `FPS`). The frames all need to be the same size.
+    pub fn animation(&self, palette: impl Fn(&T) -> Style, scale: usize, fps: f64) -> Vec<u8> {
+        check_fps(fps);
+        let images: Vec<_> = self
+            .frames()
+            .map(|grid| Image::from_grid(&grid, &palette, scale))
+            .collect();
+        let Some(first) = images.first() else {
+            return Vec::new();
+        };
+
+        let mut buf = Vec::new();
+        let mut encoder = png::Encoder::new(&mut buf, first.width as u32, first.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(images.len() as u32, 1)
+            .expect("There's at least one frame.");
+        // The delay is a fraction of a second, in milliseconds here.
+        encoder
+            .set_frame_delay((1000.0 / fps).round() as u16, 1000)
+            .expect("Set before the header is written.");
+        let mut writer = encoder
+            .write_header()
+            .expect("Writing to a Vec can't fail.");
+        for image in images.iter() {
+            assert_eq!(
+                (image.width, image.height),
+                (first.width, first.height),
+                "Frames change size."
+            );
+            writer
+                .write_image_data(image.pixels.as_flattened())
+                .expect("The image should match its size.");
+        }
+        writer.finish().expect("Writing to a Vec can't fail.");
+        buf
+    }
+
+    pub fn save_animation(
+        &self,
+        path: &Path,
+        palette: impl Fn(&T) -> Style,
+        scale: usize,
+        fps: f64,
+    ) -> io::Result<()> {
+        fs::write(path, self.animation(palette, scale, fps))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Mode, Recorder};
+    use crate::{
+        render::{Style, BLACK, WHITE},
+        utils::{Grid, Point},
+    };
+
+    fn palette(on: &bool) -> Style {
+        if *on {
+            Style::new('#', WHITE)
+        } else {
+            Style::new('.', BLACK)
+        }
+    }
+
+    /// A light moving along a row of 3.
+    fn record(mode: Mode) -> Recorder<bool> {
+        let mut recorder = Recorder::new(mode);
+        for i in 0..3 {
+            let mut grid = Grid::filled(1, 3, false);
+            grid[Point::new(0, i)] = true;
+            recorder.record(&grid);
+        }
+        recorder
+    }
+
+    #[test]
+    fn test_frames() {
+        for mode in [Mode::Full, Mode::Diffs] {
+            let frames: Vec<_> = record(mode).frames().collect();
+            assert_eq!(frames.len(), 3);
+            assert_eq!(frames[2].values(), [false, false, true]);
+        }
+    }
+
+    #[test]
+    fn test_play() {
+        let mut out = Vec::new();
+        record(Mode::Diffs).play(&mut out, palette, 1000.0).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("\x1b[2J").count(), 3);
+        assert!(out.ends_with("Frame 3 of 3\n"));
+    }
+
+    #[test]
+    fn test_animation() {
+        let png = record(Mode::Diffs).animation(palette, 2, 10.0);
+        let decoder = png::Decoder::new(png.as_slice());
+        let reader = decoder.read_info().unwrap();
+        let control = reader.info().animation_control.unwrap();
+        assert_eq!(control.num_frames, 3);
+        assert_eq!((reader.info().width, reader.info().height), (6, 2));
+
+        assert!(Recorder::<bool>::new(Mode::Full)
+            .animation(palette, 2, 10.0)
+            .is_empty());
+
+        // The slowest allowed still fits a frame delay.
+        let png = record(Mode::Full).animation(palette, 1, 0.1);
+        let reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        assert_eq!(reader.info().frame_control.unwrap().delay_num, 10000);
+    }
+
+    #[test]
+    #[should_panic(expected = "isn't between")]
+    fn test_fps_out_of_range() {
+        record(Mode::Full)
+            .play(&mut Vec::new(), palette, 0.0)
+            .unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+/*
+Drawing grids, to look at a puzzle's state without scrolling a terminal.
+
+Each day says how to draw a cell with a palette, a function from a cell to
+its `Style`: a glyph for text and a colour for ANSI text and images. From
+there a grid can be turned into
+
+1. plain text, one glyph per cell
+2. ANSI-coloured text, for the terminal
+3. an `Image`, written out as PPM or PNG
+
+`save` picks between them from the file extension.
+*/
+use std::{fs, io, iter, path::Path};
+
+use crate::utils::Grid;
+
+pub type Rgb = [u8; 3];
+
+/// Pixels per cell when saving an image.
+pub const SCALE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Style {
+    pub glyph: char,
+    pub color: Rgb,
+}
+
+impl Style {
+    pub const fn new(glyph: char, color: Rgb) -> Style {
+        Style { glyph, color }
+    }
+}
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const GREY: Rgb = [128, 128, 128];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const RED: Rgb = [220, 50, 47];
+pub const GREEN: Rgb = [0, 200, 83];
+pub const YELLOW: Rgb = [255, 193, 7];
+pub const BLUE: Rgb = [38, 139, 210];
+
+/// One line per row, without colours.
+pub fn text<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Style) -> String {
+    let mut buf = String::with_capacity(grid.n_rows() * (grid.n_cols() + 1));
+    for row in grid.rows() {
+        buf.extend(row.iter().map(|cell| palette(cell).glyph));
+        buf.push('\n');
+    }
+    buf
+}
+
+/// Like `text`, with each glyph in its colour. Runs of one colour share an
+/// escape code, and every line ends with a reset.
+pub fn ansi<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Style) -> String {
+    let mut buf = String::new();
+    for row in grid.rows() {
+        let mut current = None;
+        for style in row.iter().map(&palette) {
+            if current != Some(style.color) {
+                let [r, g, b] = style.color;
+                buf.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+                current = Some(style.color);
+            }
+            buf.push(style.glyph);
+        }
+        buf.push_str("\x1b[0m\n");
+    }
+    buf
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row from the top left.
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Each cell becomes a `scale` by `scale` square of its colour.
+    pub fn from_grid<T>(grid: &Grid<T>, palette: impl Fn(&T) -> Style, scale: usize) -> Image {
+        let (width, height) = (grid.n_cols() * scale, grid.n_rows() * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in grid.rows() {
+            let line: Vec<Rgb> = row
+                .iter()
+                .flat_map(|cell| iter::repeat_n(palette(cell).color, scale))
+                .collect();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&line);
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Binary PPM (P6), which almost any image viewer can open.
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut buf = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        buf.extend(self.pixels.iter().flatten());
+        buf
+    }
+
+    pub fn png(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        let mut encoder = png::Encoder::new(&mut buf, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .expect("Writing to a Vec can't fail.");
+        writer
+            .write_image_data(self.pixels.as_flattened())
+            .expect("The image should match its size.");
+        writer.finish().expect("Writing to a Vec can't fail.");
+        buf
+    }
+}
+
+/// Writes `grid` to `path` as a PNG or PPM image (with `scale` pixels per
+/// cell), ANSI-coloured text for `.ans`, or else plain text.
+pub fn save<T>(
+    path: &Path,
+    grid: &Grid<T>,
+    palette: impl Fn(&T) -> Style,
+    scale: usize,
+) -> io::Result<()> {
+    let contents = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("png") => Image::from_grid(grid, palette, scale).png(),
+        Some("ppm") => Image::from_grid(grid, palette, scale).ppm(),
+        Some("ans") => ansi(grid, palette).into_bytes(),
+        _ => text(grid, palette).into_bytes(),
+    };
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ansi, text, Image, Style, BLACK, WHITE};
+    use crate::utils::Grid;
+
+    fn palette(wall: &bool) -> Style {
+        if *wall {
+            Style::new('#', WHITE)
+        } else {
+            Style::new('.', BLACK)
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        let grid = Grid::new(2, 2, vec![true, true, false, true]);
+        assert_eq!(text(&grid, palette), "##\n.#\n");
+        assert_eq!(
+            ansi(&grid, palette),
+            "\x1b[38;2;255;255;255m##\x1b[0m\n\
+             \x1b[38;2;0;0;0m.\x1b[38;2;255;255;255m#\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_image() {
+        let grid = Grid::new(1, 2, vec![true, false]);
+        let image = Image::from_grid(&grid, palette, 2);
+        assert_eq!((image.width, image.height), (4, 2));
+        assert_eq!(image.pixels[..4], [WHITE, WHITE, BLACK, BLACK]);
+        assert_eq!(image.pixels[4..], image.pixels[..4]);
+
+        let ppm = image.ppm();
+        assert!(ppm.starts_with(b"P6\n4 2\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 2 * 3);
+
+        let png = image.png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut decoded).unwrap();
+        assert_eq!(decoded, image.pixels.as_flattened());
+    }
+}
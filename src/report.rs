@@ -0,0 +1,167 @@
+/*
+Machine-readable output for runs: one record per day and part, as JSON lines
+or CSV, for dashboards and CI to consume.
+*/
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::{
+    answers::Verdict,
+    runner::{Record, Status},
+    solution::Answer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub input: String,
+    pub parse_ms: Option<f64>,
+    pub solve_ms: Option<f64>,
+    pub success: bool,
+    pub error: Option<String>,
+    pub check: Option<String>,
+}
+
+pub const CSV_HEADER: &str = "day,part,answer,input,parse_ms,solve_ms,success,error,check";
+
+impl Row {
+    pub fn new(record: &Record, input: &Path) -> Row {
+        let (answer, mut error) = match &record.status {
+            Status::Solved(answer) => (Some(answer.clone()), None),
+            Status::NotImplemented => (None, Some("not implemented".to_string())),
+            Status::Panicked(message) => (None, Some(message.clone())),
+            Status::Invalid(error) => (None, Some(error.to_string())),
+            Status::MissingInput => (None, Some("missing input".to_string())),
+        };
+        let check = match &record.verdict {
+            Verdict::Unknown => None,
+            Verdict::Fail { expected } => {
+                error = Some(format!("expected {expected}"));
+                Some(record.verdict.to_string())
+            }
+            verdict => Some(verdict.to_string()),
+        };
+
+        Row {
+            day: record.day,
+            part: record.part.number(),
+            success: answer.is_some() && !record.verdict.is_failure(),
+            answer,
+            input: input.display().to_string(),
+            parse_ms: record.parse_time.map(|d| d.as_secs_f64() * 1000.0),
+            solve_ms: record.solve_time.map(|d| d.as_secs_f64() * 1000.0),
+            error,
+            check,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize row.")
+    }
+
+    pub fn to_csv(&self) -> String {
+        fn optional<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        [
+            self.day.to_string(),
+            self.part.to_string(),
+            optional(&self.answer),
+            self.input.clone(),
+            optional(&self.parse_ms),
+            optional(&self.solve_ms),
+            self.success.to_string(),
+            optional(&self.error),
+            optional(&self.check),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+/// Quotes a field if it contains anything CSV treats specially (d17's answer
+/// is itself comma separated).
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, time::Duration};
+
+    use crate::{
+        answers::Verdict,
+        runner::{Record, Status},
+        solution::{Answer, Part},
+    };
+
+    use super::{Row, CSV_HEADER};
+
+    fn record(status: Status, verdict: Verdict) -> Record {
+        Record {
+            day: 17,
+            part: Part::One,
+            status,
+            verdict,
+            parse_time: Some(Duration::from_millis(2)),
+            solve_time: Some(Duration::from_millis(5)),
+        }
+    }
+
+    #[test]
+    fn test_json() {
+        let row = Row::new(
+            &record(Status::Solved(Answer::Number(42)), Verdict::Pass),
+            Path::new("inputs/17.txt"),
+        );
+        assert_eq!(
+            row.to_json(),
+            r#"{"day":17,"part":1,"answer":42,"input":"inputs/17.txt","parse_ms":2.0,"solve_ms":5.0,"success":true,"error":null,"check":"PASS"}"#
+        );
+
+        let row = Row::new(
+            &record(Status::Panicked("oops".into()), Verdict::Unknown),
+            Path::new("-"),
+        );
+        assert!(row.to_json().contains(r#""answer":null"#));
+        assert!(row
+            .to_json()
+            .contains(r#""success":false,"error":"oops","check":null"#));
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(CSV_HEADER.split(',').count(), 9);
+
+        let row = Row::new(
+            &record(
+                Status::Solved(Answer::Text("4,6,3".into())),
+                Verdict::Fail {
+                    expected: "4,6,4".into(),
+                },
+            ),
+            Path::new("inputs/17.txt"),
+        );
+        assert_eq!(
+            row.to_csv(),
+            "17,1,\"4,6,3\",inputs/17.txt,2,5,false,\"expected 4,6,4\",FAIL"
+        );
+    }
+}
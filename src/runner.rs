@@ -0,0 +1,324 @@
+/*
+Run every registered day against its input and time it.
+
+Each day is parsed once, then both parts are solved from the parsed input.
+A panic (including `todo!()`) is caught and recorded against the day instead
+of taking the whole run down with it. Answers are checked against the
+answers file, if there is one.
+*/
+use std::{
+    any::Any,
+    fmt, fs,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    answers::{Answers, Verdict},
+    inputs,
+    solution::{Answer, Day, Parsed, Part, DAYS},
+    utils::ParseError,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved(Answer),
+    NotImplemented,
+    Panicked(String),
+    Invalid(ParseError),
+    MissingInput,
+}
+
+impl Status {
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            Status::Panicked(_) | Status::Invalid(_) | Status::MissingInput
+        )
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Solved(answer) => write!(f, "{answer}"),
+            Status::NotImplemented => write!(f, "not implemented"),
+            Status::Panicked(message) => write!(f, "FAILED: {message}"),
+            Status::Invalid(error) => write!(f, "FAILED: {error}"),
+            Status::MissingInput => write!(f, "FAILED: missing input"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: u8,
+    pub part: Part,
+    pub status: Status,
+    pub verdict: Verdict,
+    pub parse_time: Option<Duration>,
+    pub solve_time: Option<Duration>,
+}
+
+impl Record {
+    pub fn missing_input(day: u8, part: Part) -> Record {
+        Record {
+            day,
+            part,
+            status: Status::MissingInput,
+            verdict: Verdict::Unknown,
+            parse_time: None,
+            solve_time: None,
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.status.is_failure() || self.verdict.is_failure()
+    }
+
+    pub fn check(&mut self, answers: &Answers) {
+        if let Status::Solved(answer) = &self.status {
+            self.verdict = answers.check(self.day, self.part, answer);
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> Result<(T, Duration), String> {
+    let start = Instant::now();
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map(|value| (value, start.elapsed()))
+        .map_err(panic_message)
+}
+
+/// Parsing can fail by panicking as well as by returning an error.
+fn parse(day: &Day, input: &str) -> Result<(Parsed, Duration), Status> {
+    match timed(|| day.parse(input)) {
+        Ok((Ok(parsed), elapsed)) => Ok((parsed, elapsed)),
+        Ok((Err(error), _)) => Err(Status::Invalid(error)),
+        Err(message) => Err(Status::Panicked(message)),
+    }
+}
+
+fn solve(day: &Day, parsed: &Result<(Parsed, Duration), Status>, part: Part) -> Record {
+    match parsed {
+        Err(status) => Record {
+            day: day.day,
+            part,
+            status: status.clone(),
+            verdict: Verdict::Unknown,
+            parse_time: None,
+            solve_time: None,
+        },
+        Ok((parsed, parse_time)) => {
+            let (status, solve_time) = match timed(|| day.solve(parsed, part)) {
+                Ok((Some(answer), elapsed)) => (Status::Solved(answer), Some(elapsed)),
+                Ok((None, _)) => (Status::NotImplemented, None),
+                Err(message) => (Status::Panicked(message), None),
+            };
+            Record {
+                day: day.day,
+                part,
+                status,
+                verdict: Verdict::Unknown,
+                parse_time: Some(*parse_time),
+                solve_time,
+            }
+        }
+    }
+}
+
+pub fn run_part(day: &Day, input: &str, part: Part) -> Record {
+    let parsed = parse(day, input);
+    solve(day, &parsed, part)
+}
+
+pub fn run_day(day: &Day, input: &str) -> Vec<Record> {
+    let parsed = parse(day, input);
+    Part::ALL
+        .iter()
+        .map(|&part| solve(day, &parsed, part))
+        .collect()
+}
+
+/// For calls that bypass the registry, e.g. a day run with non-default
+/// parameters. `parse` and `solve` are timed separately, like a registered
+/// day's, and a parse error is reported the same way.
+pub fn run_custom<T>(
+    day: u8,
+    part: Part,
+    parse: impl FnOnce() -> Result<T, ParseError>,
+    solve: impl FnOnce(T) -> Answer,
+) -> Record {
+    let record = |status, parse_time, solve_time| Record {
+        day,
+        part,
+        status,
+        verdict: Verdict::Unknown,
+        parse_time,
+        solve_time,
+    };
+    let (parsed, parse_time) = match timed(parse) {
+        Ok((Ok(parsed), elapsed)) => (parsed, elapsed),
+        Ok((Err(error), _)) => return record(Status::Invalid(error), None, None),
+        Err(message) => return record(Status::Panicked(message), None, None),
+    };
+    match timed(|| solve(parsed)) {
+        Ok((answer, elapsed)) => record(Status::Solved(answer), Some(parse_time), Some(elapsed)),
+        Err(message) => record(Status::Panicked(message), Some(parse_time), None),
+    }
+}
+
+pub fn run_all(inputs: &Path, answers: &Answers) -> Vec<Record> {
+    // The default hook would print every caught panic in the middle of the table.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut records = Vec::with_capacity(DAYS.len() * 2);
+    for day in DAYS.iter() {
+        match fs::read_to_string(inputs::path(inputs, day.day)) {
+            Ok(input) => records.extend(run_day(day, &input)),
+            Err(_) => records.extend(
+                Part::ALL
+                    .iter()
+                    .map(|&part| Record::missing_input(day.day, part)),
+            ),
+        }
+    }
+
+    panic::set_hook(hook);
+
+    for record in records.iter_mut() {
+        record.check(answers);
+    }
+    records
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(d) => format!("{:.3}ms", d.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+pub fn format_table(records: &[Record]) -> String {
+    let answers: Vec<_> = records.iter().map(|r| r.status.to_string()).collect();
+    let width = answers.iter().map(|a| a.len()).max().unwrap_or(0).max(6);
+
+    let mut buf = format!(
+        "{:>3} {:>4}  {:<width$}  {:<11}  {:>12} {:>12}\n",
+        "day", "part", "answer", "check", "parse", "solve"
+    );
+    for (record, answer) in records.iter().zip(answers) {
+        buf.push_str(&format!(
+            "{:>3} {:>4}  {:<width$}  {:<11}  {:>12} {:>12}\n",
+            record.day,
+            record.part,
+            answer,
+            record.verdict,
+            format_duration(record.parse_time),
+            format_duration(record.solve_time),
+        ));
+    }
+
+    let total: Duration = records
+        .iter()
+        .filter_map(|r| r.solve_time)
+        .chain(
+            records
+                .iter()
+                .filter(|r| r.part == Part::One)
+                .filter_map(|r| r.parse_time),
+        )
+        .sum();
+    let failed = records.iter().filter(|r| r.is_failure()).count();
+    buf.push_str(&format!(
+        "{} parts, {failed} failed, {} total\n",
+        records.len(),
+        format_duration(Some(total))
+    ));
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        answers::{self, Verdict},
+        solution::{get, Answer, Part},
+    };
+
+    use super::{format_table, run_custom, run_day, Status};
+
+    #[test]
+    fn test_run_day() {
+        let records = run_day(get(1).unwrap(), "3   4\n4   3\n2   5\n1   3\n3   9\n3   3");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].part, Part::One);
+        assert_eq!(records[0].status, Status::Solved(Answer::Number(11)));
+        assert_eq!(records[1].status, Status::Solved(Answer::Number(31)));
+        assert!(records[1].parse_time.is_some());
+        assert!(records[1].solve_time.is_some());
+
+        let table = format_table(&records);
+        assert!(table.contains("2 parts, 0 failed"));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = answers::parse_input("1 1 11\n1 2 32").unwrap();
+        let mut records = run_day(get(1).unwrap(), "3   4\n4   3\n2   5\n1   3\n3   9\n3   3");
+        for record in records.iter_mut() {
+            record.check(&answers);
+        }
+        assert_eq!(records[0].verdict, Verdict::Pass);
+        assert!(records[1].is_failure());
+
+        let table = format_table(&records);
+        assert!(table.contains("2 parts, 1 failed"));
+    }
+
+    #[test]
+    fn test_run_day_failures() {
+        // d21 is still `todo!()` and d09 has no part 2.
+        let records = run_day(get(21).unwrap(), "029A");
+        assert!(matches!(records[0].status, Status::Panicked(_)));
+
+        let records = run_day(get(9).unwrap(), "2333133121414131402");
+        assert_eq!(records[0].status, Status::Solved(Answer::Number(1928)));
+        assert_eq!(records[1].status, Status::NotImplemented);
+        assert!(!records[1].status.is_failure());
+
+        let records = run_day(get(1).unwrap(), "3   4\n4   x");
+        assert!(matches!(records[0].status, Status::Invalid(_)));
+        assert!(records.iter().all(|r| r.is_failure()));
+    }
+
+    #[test]
+    fn test_run_custom() {
+        let record = run_custom(1, Part::One, || Ok(2), |n| Answer::Number(n * 3));
+        assert_eq!(record.status, Status::Solved(Answer::Number(6)));
+        assert!(record.parse_time.is_some());
+
+        let record = run_custom(
+            11,
+            Part::One,
+            || crate::d11::try_parse_input("1 x"),
+            |_| unreachable!(),
+        );
+        assert!(matches!(record.status, Status::Invalid(_)));
+
+        let record = run_custom(1, Part::One, || Ok(()), |_| panic!("Oops."));
+        assert_eq!(record.status, Status::Panicked("Oops.".to_string()));
+        assert!(record.is_failure());
+    }
+}
@@ -0,0 +1,244 @@
+/*
+Starting a new day: `new-day NN` writes `src/dNN.rs` from a template and
+registers it everywhere it needs to be,
+
+1. `pub mod dNN;` in `src/lib.rs`
+2. its `Solver` in `DAYS`, in `src/solution.rs`
+3. a `DNN` subcommand and its match arm, in `src/main.rs`
+
+Each is inserted in day order, formatted the way `cargo fmt` would leave it.
+Nothing is written unless every file can be updated.
+*/
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub fn template(day: u8) -> String {
+    format!(
+        r##"/*
+https://adventofcode.com/2024/day/{day}
+*/
+use crate::{{
+    solution::{{Answer, Solution}},
+    utils::{{ParseError, Source}},
+}};
+
+pub fn parse_input(input: &str) -> Vec<String> {{
+    try_parse_input(input).unwrap_or_else(|e| panic!("{{e}}"))
+}}
+
+pub fn try_parse_input(input: &str) -> Result<Vec<String>, ParseError> {{
+    let _source = Source::new({day}, input);
+    Ok(input.lines().map(String::from).collect())
+}}
+
+pub fn part1(lines: &[String]) -> Option<u64> {{
+    let _ = lines;
+    None
+}}
+
+pub fn part2(lines: &[String]) -> Option<u64> {{
+    let _ = lines;
+    None
+}}
+
+pub struct Solver;
+
+impl Solution for Solver {{
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {{
+        try_parse_input(input)
+    }}
+
+    fn part1(input: &Vec<String>) -> Option<Answer> {{
+        part1(input).map(Answer::from)
+    }}
+
+    fn part2(input: &Vec<String>) -> Option<Answer> {{
+        part2(input).map(Answer::from)
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::{{parse_input, part1, part2}};
+
+    const INPUT: &str = "\
+";
+
+    #[test]
+    #[ignore = "needs the example"]
+    fn test_part_1_example() {{
+        assert_eq!(part1(&parse_input(INPUT)), Some(0));
+    }}
+
+    #[test]
+    #[ignore = "needs the example"]
+    fn test_part_2_example() {{
+        assert_eq!(part2(&parse_input(INPUT)), Some(0));
+    }}
+}}
+"##
+    )
+}
+
+/// The days that already have a line starting with `prefix` followed by the
+/// day, e.g. `pub mod d` for "pub mod d07;".
+fn existing_days(source: &str, prefix: &str) -> Vec<(u8, usize)> {
+    let mut offset = 0;
+    let mut days = Vec::new();
+    for line in source.split_inclusive('\n') {
+        if let Some(rest) = line.trim_start().strip_prefix(prefix) {
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            if let Ok(day) = digits.parse() {
+                days.push((day, offset));
+            }
+        }
+        offset += line.len();
+    }
+    days
+}
+
+/// Inserts `text` before the first registered day after `day`, or before
+/// `fallback` if `day` is the last one.
+fn insert(
+    source: &str,
+    day: u8,
+    prefix: &str,
+    text: &str,
+    fallback: &str,
+) -> Result<String, String> {
+    let days = existing_days(source, prefix);
+    if days.iter().any(|&(d, _)| d == day) {
+        return Err(format!("day {day} is already registered"));
+    }
+
+    let at = match days.iter().find(|&&(d, _)| d > day) {
+        Some(&(_, offset)) => offset,
+        None => source
+            .find(fallback)
+            .ok_or_else(|| format!("couldn't find '{}'", fallback.trim()))?,
+    };
+    Ok([&source[..at], text, &source[at..]].concat())
+}
+
+pub fn register_module(lib_rs: &str, day: u8) -> Result<String, String> {
+    insert(
+        lib_rs,
+        day,
+        "pub mod d",
+        &format!("pub mod d{day:02};\n"),
+        "\npub mod answers;",
+    )
+}
+
+pub fn register_solver(solution_rs: &str, day: u8) -> Result<String, String> {
+    insert(
+        solution_rs,
+        day,
+        "Day::new::<crate::d",
+        &format!("    Day::new::<crate::d{day:02}::Solver>({day}),\n"),
+        "];\n\npub fn get(",
+    )
+}
+
+pub fn register_command(main_rs: &str, day: u8) -> Result<String, String> {
+    let with_variant = insert(
+        main_rs,
+        day,
+        "D",
+        &format!("    D{day} {{\n        #[command(flatten)]\n        args: DayArgs,\n    }},\n"),
+        "\n    /// Run every day against its input",
+    )?;
+
+    insert(
+        &with_variant,
+        day,
+        "Commands::D",
+        &format!("            Commands::D{day} {{ args }} => ({day}, args.part, &args.input),\n"),
+        "\n            Commands::RunAll",
+    )
+}
+
+/// Writes the new module and registers it, returning the files it touched.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, String> {
+    let src = root.join("src");
+    let module = src.join(format!("d{day:02}.rs"));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+
+    let read = |name: &str| {
+        let path = src.join(name);
+        fs::read_to_string(&path)
+            .map(|source| (path.clone(), source))
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))
+    };
+    let (lib, lib_rs) = read("lib.rs")?;
+    let (solution, solution_rs) = read("solution.rs")?;
+    let (main, main_rs) = read("main.rs")?;
+
+    let updates = [
+        (module, template(day)),
+        (lib, register_module(&lib_rs, day)?),
+        (solution, register_solver(&solution_rs, day)?),
+        (main, register_command(&main_rs, day)?),
+    ];
+    for (path, source) in updates.iter() {
+        fs::write(path, source)
+            .map_err(|err| format!("Failed to write {}: {err}", path.display()))?;
+    }
+    Ok(updates.into_iter().map(|(path, _)| path).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_command, register_module, register_solver};
+
+    const LIB_RS: &str = include_str!("lib.rs");
+    const SOLUTION_RS: &str = include_str!("solution.rs");
+    const MAIN_RS: &str = include_str!("main.rs");
+
+    /* Taking a day out of the real sources and registering it again should
+    give back exactly what's there. */
+
+    #[test]
+    fn test_register_module() {
+        for day in [1, 21, 25] {
+            let without = LIB_RS.replace(&format!("pub mod d{day:02};\n"), "");
+            assert_eq!(register_module(&without, day).unwrap(), LIB_RS);
+        }
+        assert!(register_module(LIB_RS, 21).is_err());
+    }
+
+    #[test]
+    fn test_register_solver() {
+        for day in [1, 21, 25] {
+            let without = SOLUTION_RS.replace(
+                &format!("    Day::new::<crate::d{day:02}::Solver>({day}),\n"),
+                "",
+            );
+            assert_eq!(register_solver(&without, day).unwrap(), SOLUTION_RS);
+        }
+        assert!(register_solver(SOLUTION_RS, 21).is_err());
+    }
+
+    #[test]
+    fn test_register_command() {
+        for day in [21, 25] {
+            let without = MAIN_RS
+                .replace(
+                    &format!("    D{day} {{\n        #[command(flatten)]\n        args: DayArgs,\n    }},\n"),
+                    "",
+                )
+                .replace(
+                    &format!("            Commands::D{day} {{ args }} => ({day}, args.part, &args.input),\n"),
+                    "",
+                );
+            assert_eq!(register_command(&without, day).unwrap(), MAIN_RS);
+        }
+        assert!(register_command(MAIN_RS, 21).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+/*
+Shortest paths over any graph given as a neighbour callback.
+
+`bfs` is for unit steps and `dijkstra` for weighted ones. Both explore
+everything reachable from the start and return a `Search`: the distance to
+each node, and every predecessor that reaches it at that distance. Keeping
+all of them (not just the first) means the predecessors form a DAG of all
+the shortest paths, so "which nodes are on *a* best path" is a walk back
+from the targets.
+
+`astar` stops as soon as it reaches a goal, for when there's one answer and
+a good estimate of the remaining cost.
+
+Unreachable targets come back as `None` throughout.
+*/
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+pub struct Search<N> {
+    pub start: N,
+    pub distances: HashMap<N, u64>,
+    /// Every node that reaches the key on a shortest path. Empty for the start.
+    pub predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new(start: N) -> Search<N> {
+        Search {
+            distances: HashMap::from([(start.clone(), 0)]),
+            predecessors: HashMap::from([(start.clone(), Vec::new())]),
+            start,
+        }
+    }
+
+    /// Records reaching `to` from `from` at `distance`, returning whether it's
+    /// the first time at that distance (i.e. `to` needs exploring).
+    fn relax(&mut self, from: &N, to: N, distance: u64) -> bool {
+        match self.distances.entry(to.clone()) {
+            Entry::Occupied(mut entry) => {
+                if distance > *entry.get() {
+                    return false;
+                }
+                let predecessors = self.predecessors.entry(to).or_default();
+                if distance < *entry.get() {
+                    entry.insert(distance);
+                    predecessors.clear();
+                    predecessors.push(from.clone());
+                    true
+                } else {
+                    // A duplicate edge would otherwise count twice.
+                    if !predecessors.contains(from) {
+                        predecessors.push(from.clone());
+                    }
+                    false
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(distance);
+                self.predecessors.insert(to, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    /// The closest of `targets`, with its distance.
+    pub fn nearest<'a>(&self, targets: impl IntoIterator<Item = &'a N>) -> Option<(&'a N, u64)>
+    where
+        N: 'a,
+    {
+        targets
+            .into_iter()
+            .filter_map(|t| Some((t, self.distance(t)?)))
+            .min_by_key(|&(_, d)| d)
+    }
+
+    /// One shortest path from the start to `to`, both included. Zero-cost
+    /// steps can make the predecessors loop, so it stops at the start or at
+    /// the first repeat, whichever comes first.
+    pub fn path(&self, to: &N) -> Option<Vec<N>> {
+        self.distances.get(to)?;
+        let mut path = vec![to.clone()];
+        let mut seen = HashSet::from([to]);
+        let mut node = to;
+        while *node != self.start {
+            let Some(previous) = self.predecessors[node].first() else {
+                break;
+            };
+            if !seen.insert(previous) {
+                break;
+            }
+            path.push(previous.clone());
+            node = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every node on some shortest path from the start to any of the nearest
+    /// `targets`. Empty if none of them can be reached.
+    pub fn on_shortest_paths<'a>(&self, targets: impl IntoIterator<Item = &'a N>) -> HashSet<N>
+    where
+        N: 'a,
+    {
+        let targets: Vec<_> = targets.into_iter().collect();
+        let mut result = HashSet::new();
+        let Some((_, best)) = self.nearest(targets.iter().copied()) else {
+            return result;
+        };
+
+        let mut stack: Vec<&N> = targets
+            .into_iter()
+            .filter(|t| self.distance(t) == Some(best))
+            .collect();
+        while let Some(node) = stack.pop() {
+            if result.insert(node.clone()) {
+                stack.extend(self.predecessors[node].iter());
+            }
+        }
+        result
+    }
+}
+
+/// Shortest paths where every step costs 1.
+pub fn bfs<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, distance)) = queue.pop_front() {
+        for next in neighbors(&node) {
+            if search.relax(&node, next.clone(), distance + 1) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    search
+}
+
+/// Shortest paths where `neighbors` gives the cost of each step.
+pub fn dijkstra<N, I>(start: N, mut neighbors: impl FnMut(&N) -> I) -> Search<N>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::new(start.clone());
+    // Rust's heap pops the largest, so wrap the distance to get the smallest.
+    let mut queue = BinaryHeap::from([(Reverse(0), start)]);
+
+    while let Some((Reverse(distance), node)) = queue.pop() {
+        if search.distances[&node] < distance {
+            // Already reached some cheaper way.
+            continue;
+        }
+        for (next, cost) in neighbors(&node) {
+            if search.relax(&node, next.clone(), distance + cost) {
+                queue.push((Reverse(distance + cost), next));
+            }
+        }
+    }
+    search
+}
+
+/// The cost of the cheapest path to a goal, and the path itself. `heuristic`
+/// estimates the remaining cost and mustn't overestimate it.
+pub fn astar<N, I>(
+    start: N,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> u64,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = BinaryHeap::from([(Reverse(heuristic(&start)), Reverse(0), start)]);
+
+    while let Some((_, Reverse(distance), node)) = queue.pop() {
+        if search.distances[&node] < distance {
+            continue;
+        }
+        if is_goal(&node) {
+            let path = search.path(&node)?;
+            return Some((distance, path));
+        }
+        for (next, cost) in neighbors(&node) {
+            if search.relax(&node, next.clone(), distance + cost) {
+                let estimate = distance + cost + heuristic(&next);
+                queue.push((Reverse(estimate), Reverse(distance + cost), next));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{astar, bfs, dijkstra};
+
+    /* A diamond 0 -> {1, 2} -> 3, then on to 4, with 5 off on its own.
+
+         1
+        / \
+       0   3 - 4    5
+        \ /
+         2
+    */
+    fn edges(node: &u32) -> Vec<(u32, u64)> {
+        match node {
+            0 => vec![(1, 1), (2, 1)],
+            1 | 2 => vec![(3, 1)],
+            3 => vec![(4, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let search = bfs(0, |n| edges(n).into_iter().map(|(m, _)| m));
+        assert_eq!(search.distance(&4), Some(3));
+        assert_eq!(search.distance(&5), None);
+        assert_eq!(search.path(&4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(search.path(&0), Some(vec![0]));
+        assert_eq!(search.path(&5), None);
+        assert_eq!(search.predecessors[&3], vec![1, 2]);
+        assert_eq!(
+            search.on_shortest_paths(&[4]),
+            HashSet::from([0, 1, 2, 3, 4])
+        );
+        assert!(search.on_shortest_paths(&[5]).is_empty());
+        assert_eq!(search.nearest(&[4, 1, 5]), Some((&1, 1)));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Going through 2 now costs more, so it's not on the best path.
+        let search = dijkstra(0, |&n| {
+            let mut e = edges(&n);
+            if n == 0 {
+                e[1].1 = 5;
+            }
+            e
+        });
+        assert_eq!(search.distance(&3), Some(2));
+        assert_eq!(search.distance(&2), Some(5));
+        assert_eq!(search.predecessors[&3], vec![1]);
+        assert_eq!(search.on_shortest_paths(&[4]), HashSet::from([0, 1, 3, 4]));
+    }
+
+    #[test]
+    fn test_zero_cost_loops() {
+        // 0 and 1 lead to each other for free, and 1 -> 2 twice over.
+        let search = dijkstra(0, |&n| match n {
+            0 => vec![(1, 0)],
+            1 => vec![(0, 0), (2, 1), (2, 1)],
+            _ => vec![],
+        });
+        assert_eq!(search.predecessors[&2], vec![1]);
+        assert_eq!(search.path(&2), Some(vec![0, 1, 2]));
+        assert_eq!(search.path(&0), Some(vec![0]));
+        assert_eq!(search.on_shortest_paths(&[2]), HashSet::from([0, 1, 2]));
+    }
+
+    #[test]
+    fn test_astar() {
+        let (cost, path) = astar(
+            0,
+            edges,
+            |&n| 4u64.saturating_sub(n as u64).min(1),
+            |&n| n == 4,
+        )
+        .expect("4 is reachable");
+        assert_eq!(cost, 3);
+        // Either way round the diamond.
+        assert_eq!((path.len(), path[0], path[2], path[3]), (4, 0, 3, 4));
+        assert_eq!(astar(0, edges, |_| 0, |&n| n == 5), None);
+    }
+}
@@ -0,0 +1,235 @@
+/*
+A common shape for every day.
+
+Each `dNN` module has a `Solver` implementing `Solution`: parse the puzzle
+input once, then answer either part from the parsed input. `DAYS` is the
+registry of all of them, type-erased so that the binary (or anything else
+that wants to run the whole calendar) can just iterate over it.
+*/
+use std::{any::Any, fmt, str::FromStr};
+
+use serde::Serialize;
+
+use crate::utils::ParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    /// For the older per-day CLI flags (`--damped`, `--as-score`, ...), which
+    /// switch to part two when set.
+    pub fn from_flag(part_two: bool) -> Part {
+        if part_two {
+            Part::Two
+        } else {
+            Part::One
+        }
+    }
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("Invalid part '{s}', expected 1 or 2")),
+        }
+    }
+}
+
+pub trait Solution {
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// `None` means the part hasn't been implemented (yet).
+    fn part1(input: &Self::Input) -> Option<Answer>;
+
+    fn part2(_input: &Self::Input) -> Option<Answer> {
+        None
+    }
+}
+
+/// The output of `Day::parse`, to be handed back to `Day::solve`.
+pub type Parsed = Box<dyn Any>;
+
+pub struct Day {
+    pub day: u8,
+    parse: fn(&str) -> Result<Parsed, ParseError>,
+    part1: fn(&dyn Any) -> Option<Answer>,
+    part2: fn(&dyn Any) -> Option<Answer>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(day: u8) -> Day {
+        Day {
+            day,
+            parse: parse_erased::<S>,
+            part1: part1_erased::<S>,
+            part2: part2_erased::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed, ParseError> {
+        (self.parse)(input)
+    }
+
+    pub fn solve(&self, parsed: &Parsed, part: Part) -> Option<Answer> {
+        match part {
+            Part::One => (self.part1)(parsed.as_ref()),
+            Part::Two => (self.part2)(parsed.as_ref()),
+        }
+    }
+
+    pub fn run(&self, input: &str, part: Part) -> Result<Option<Answer>, ParseError> {
+        Ok(self.solve(&self.parse(input)?, part))
+    }
+}
+
+fn parse_erased<S: Solution>(input: &str) -> Result<Parsed, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn downcast<S: Solution>(parsed: &dyn Any) -> &S::Input {
+    parsed
+        .downcast_ref()
+        .expect("Parsed input from a different day.")
+}
+
+fn part1_erased<S: Solution>(parsed: &dyn Any) -> Option<Answer> {
+    S::part1(downcast::<S>(parsed))
+}
+
+fn part2_erased<S: Solution>(parsed: &dyn Any) -> Option<Answer> {
+    S::part2(downcast::<S>(parsed))
+}
+
+pub static DAYS: &[Day] = &[
+    Day::new::<crate::d01::Solver>(1),
+    Day::new::<crate::d02::Solver>(2),
+    Day::new::<crate::d03::Solver>(3),
+    Day::new::<crate::d04::Solver>(4),
+    Day::new::<crate::d05::Solver>(5),
+    Day::new::<crate::d06::Solver>(6),
+    Day::new::<crate::d07::Solver>(7),
+    Day::new::<crate::d08::Solver>(8),
+    Day::new::<crate::d09::Solver>(9),
+    Day::new::<crate::d10::Solver>(10),
+    Day::new::<crate::d11::Solver>(11),
+    Day::new::<crate::d12::Solver>(12),
+    Day::new::<crate::d13::Solver>(13),
+    Day::new::<crate::d14::Solver>(14),
+    Day::new::<crate::d15::Solver>(15),
+    Day::new::<crate::d16::Solver>(16),
+    Day::new::<crate::d17::Solver>(17),
+    Day::new::<crate::d18::Solver>(18),
+    Day::new::<crate::d19::Solver>(19),
+    Day::new::<crate::d20::Solver>(20),
+    Day::new::<crate::d21::Solver>(21),
+    Day::new::<crate::d22::Solver>(22),
+    Day::new::<crate::d23::Solver>(23),
+    Day::new::<crate::d24::Solver>(24),
+    Day::new::<crate::d25::Solver>(25),
+];
+
+pub fn get(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get, Answer, Part, DAYS};
+
+    #[test]
+    fn test_registry() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, i + 1);
+        }
+        assert!(get(0).is_none());
+        assert!(get(26).is_none());
+    }
+
+    #[test]
+    fn test_run() {
+        let input = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3";
+        let day = get(1).unwrap();
+        let parsed = day.parse(input).unwrap();
+        assert_eq!(day.solve(&parsed, Part::One), Some(Answer::Number(11)));
+        assert_eq!(day.solve(&parsed, Part::Two), Some(Answer::Number(31)));
+    }
+
+    #[test]
+    fn test_not_implemented() {
+        let day = get(9).unwrap();
+        assert_eq!(day.run("2333133121414131402", Part::Two), Ok(None));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let error = get(1).unwrap().run("3   4\n4   x", Part::One).unwrap_err();
+        assert_eq!((error.day, error.line, error.column), (1, 2, 5));
+    }
+}
@@ -1,59 +1,1198 @@
+use std::{collections::HashMap, error::Error, fmt, ops, str::FromStr};
+
+use serde::Serialize;
+
+/// A rectangular grid, stored row by row in one `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Grid<T> {
-    pub values: Vec<Vec<T>>,
+    values: Vec<T>,
+    n_rows: usize,
+    n_cols: usize,
+}
+
+/// Where the marked characters (start, end, robot, ...) were found by
+/// `Grid::parse`, as `(row, col)`.
+#[derive(Debug, Default)]
+pub struct Marks {
+    positions: HashMap<char, Vec<(usize, usize)>>,
+}
+
+impl Marks {
+    pub fn all(&self, mark: char) -> &[(usize, usize)] {
+        self.positions.get(&mark).map_or(&[], |p| p.as_slice())
+    }
+
+    /// The position of a mark that should appear exactly once, with `what` to
+    /// describe it in the error if it doesn't.
+    pub fn one(
+        &self,
+        source: &Source,
+        mark: char,
+        what: &str,
+    ) -> Result<(usize, usize), ParseError> {
+        match self.all(mark) {
+            [position] => Ok(*position),
+            [] => Err(source.error(source.end(), format!("expected {what} ({mark})"))),
+            _ => Err(source.error(source.end(), format!("expected {what} ({mark}) only once"))),
+        }
+    }
 }
 
 impl<T> Grid<T> {
+    /// From `values` row by row, which must be exactly `n_rows * n_cols` long.
+    pub fn new(n_rows: usize, n_cols: usize, values: Vec<T>) -> Grid<T> {
+        assert_eq!(values.len(), n_rows * n_cols, "Wrong number of cells.");
+        Grid {
+            values,
+            n_rows,
+            n_cols,
+        }
+    }
+
+    pub fn filled(n_rows: usize, n_cols: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(n_rows, n_cols, vec![value; n_rows * n_cols])
+    }
+
+    /// Panics unless every row is the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let n_rows = rows.len();
+        let n_cols = rows.first().map_or(0, Vec::len);
+        assert!(rows.iter().all(|row| row.len() == n_cols), "Ragged rows.");
+        Grid::new(n_rows, n_cols, rows.into_iter().flatten().collect())
+    }
+
+    /// Builds a grid from `text` (all or part of `source`'s input), one row per
+    /// line. `cell` maps each character to a cell, with `None` rejecting it.
+    /// The positions of any `marks` characters are collected as well; they
+    /// still need a cell of their own, e.g. 'S' might be open ground.
+    pub fn parse<'a>(
+        source: &Source<'a>,
+        text: &'a str,
+        marks: &[char],
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<(Grid<T>, Marks), ParseError> {
+        source.check_rectangular(text)?;
+
+        let mut values = Vec::with_capacity(text.len());
+        let mut found = Marks::default();
+        let mut n_rows = 0;
+        for (row, line) in text.lines().enumerate() {
+            for (col, (i, c)) in line.char_indices().enumerate() {
+                if marks.contains(&c) {
+                    found.positions.entry(c).or_default().push((row, col));
+                }
+                values.push(cell(c).ok_or_else(|| source.unexpected_char(line, i))?);
+            }
+            n_rows += 1;
+        }
+        let n_cols = values.len().checked_div(n_rows).unwrap_or(0);
+        Ok((Grid::new(n_rows, n_cols, values), found))
+    }
+
+    /// Every cell, row by row.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    /// Every cell with its `(row, col)`, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let n_cols = self.n_cols;
+        self.values
+            .iter()
+            .enumerate()
+            .map(move |(i, value)| ((i / n_cols, i % n_cols), value))
+    }
+
     pub fn n_rows(&self) -> usize {
-        self.values.len()
+        self.n_rows
     }
 
     pub fn n_cols(&self) -> usize {
-        self.values[0].len()
+        self.n_cols
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.row < self.n_rows && p.col < self.n_cols
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.iloc(p.row, p.col)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        let n_cols = self.n_cols;
+        self.contains(p)
+            .then(|| &mut self.values[p.row * n_cols + p.col])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.values[row * self.n_cols..(row + 1) * self.n_cols]
+    }
+
+    /// Top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.n_rows).map(|row| self.row(row))
+    }
+
+    /// Top to bottom.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        assert!(col < self.n_cols, "No column {col}.");
+        self.values[col..].iter().step_by(self.n_cols)
+    }
+
+    /// Left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.n_cols).map(|col| self.column(col))
+    }
+
+    /// How many diagonals there are each way; none if the grid is empty.
+    fn n_diagonals(&self) -> usize {
+        if self.n_rows == 0 || self.n_cols == 0 {
+            0
+        } else {
+            self.n_rows + self.n_cols - 1
+        }
+    }
+
+    /// The diagonals running down and to the right, each from the top left,
+    /// starting with the one in the bottom left corner and ending with the one
+    /// in the top right corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (n_rows, n_cols) = (self.n_rows, self.n_cols);
+        (0..self.n_diagonals()).map(move |k| {
+            let (row, col) = if k < n_rows {
+                (n_rows - 1 - k, 0)
+            } else {
+                (0, k + 1 - n_rows)
+            };
+            let len = (n_rows - row).min(n_cols - col);
+            self.values[row * n_cols + col..]
+                .iter()
+                .step_by(n_cols + 1)
+                .take(len)
+        })
+    }
+
+    /// The diagonals running down and to the left, each from the top right,
+    /// starting with the one in the top left corner and ending with the one
+    /// in the bottom right corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        let (n_rows, n_cols) = (self.n_rows, self.n_cols);
+        (0..self.n_diagonals()).map(move |k| {
+            let (row, col) = if k < n_cols {
+                (0, k)
+            } else {
+                (k + 1 - n_cols, n_cols - 1)
+            };
+            let len = (n_rows - row).min(col + 1);
+            self.values[row * n_cols + col..]
+                .iter()
+                .step_by((n_cols - 1).max(1))
+                .take(len)
+        })
     }
 
     pub fn up(&self, row: usize, col: usize) -> Option<&T> {
-        self.iloc(row - 1, col)
+        self.iloc(row.checked_sub(1)?, col)
     }
 
     pub fn down(&self, row: usize, col: usize) -> Option<&T> {
-        self.iloc(row + 1, col)
+        self.iloc(row.checked_add(1)?, col)
     }
 
     pub fn left(&self, row: usize, col: usize) -> Option<&T> {
-        self.iloc(row, col - 1)
+        self.iloc(row, col.checked_sub(1)?)
     }
+
     pub fn right(&self, row: usize, col: usize) -> Option<&T> {
-        self.iloc(row, col + 1)
+        self.iloc(row, col.checked_add(1)?)
     }
 
     pub fn iloc(&self, row: usize, col: usize) -> Option<&T> {
-        self.values.get(row)?.get(col)
-    }
-    //     eprintln!("row={row:} col={col}");
-    //     self.values.get(0).or_else(|r| r.get)
-    //     if (row >= self.n_rows()) || (col == 0 || col == self.n_cols() - 1) {
-    //         None
-    //     } else {
-    //         Some(self.values[row][col])
-    //     }
-    // }
-
-    pub fn neighbors(&self, row: usize, col: usize) -> Vec<(usize, usize)> {
-        let choices = [
-            (row.checked_sub(1), Some(col)),
-            (row.checked_add(1), Some(col)),
-            (Some(row), col.checked_sub(1)),
-            (Some(row), col.checked_add(1)),
-        ];
-        choices
+        self.contains(Point::new(row, col))
+            .then(|| &self.values[row * self.n_cols + col])
+    }
+
+    /// The cell `(d_row, d_col)` away, or `None` past an edge.
+    pub fn offset(
+        &self,
+        row: usize,
+        col: usize,
+        d_row: isize,
+        d_col: isize,
+    ) -> Option<(usize, usize)> {
+        let row = row
+            .checked_add_signed(d_row)
+            .filter(|&r| r < self.n_rows())?;
+        let col = col
+            .checked_add_signed(d_col)
+            .filter(|&c| c < self.n_cols())?;
+        Some((row, col))
+    }
+
+    /// The cell `(d_row, d_col)` away on a torus: walking off one edge comes
+    /// back on the opposite one.
+    pub fn wrapping_offset(
+        &self,
+        row: usize,
+        col: usize,
+        d_row: isize,
+        d_col: isize,
+    ) -> (usize, usize) {
+        Point::new(row, col)
+            .wrapping_add(Vector::new(d_row, d_col), self.n_rows(), self.n_cols())
+            .into()
+    }
+
+    /// The (up to) 4 cells sharing an edge with `(row, col)`, clockwise from up.
+    pub fn neighbors(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINAL
+            .iter()
+            .filter_map(move |d| self.offset(row, col, d.offset().row, d.offset().col))
+    }
+
+    /// The (up to) 8 cells sharing an edge or a corner with `(row, col)`,
+    /// clockwise from up.
+    pub fn neighbors8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
+            .iter()
+            .filter_map(move |d| self.offset(row, col, d.offset().row, d.offset().col))
+    }
+
+    /// Like `neighbors`, but wrapping around the edges, so there are always 4.
+    pub fn wrapping_neighbors(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::CARDINAL
+            .iter()
+            .map(move |d| self.wrapping_offset(row, col, d.offset().row, d.offset().col))
+    }
+
+    /// Like `neighbors8`, but wrapping around the edges, so there are always 8.
+    pub fn wrapping_neighbors8(
+        &self,
+        row: usize,
+        col: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        Direction::ALL
             .iter()
-            .filter(|(r, c)| {
-                r.is_some()
-                    && c.is_some()
-                    && r.unwrap() < self.n_rows()
-                    && c.unwrap() < self.n_cols()
-            })
-            .map(|a| (a.0.unwrap(), a.1.unwrap()))
-            .collect()
+            .map(move |d| self.wrapping_offset(row, col, d.offset().row, d.offset().col))
+    }
+}
+
+/// A group of equal cells, connected through their edges, found by
+/// `Grid::regions`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Its index in `Regions::regions`, and its cells' value in `labels`.
+    pub label: usize,
+    pub area: usize,
+    /// Edges between the region and anything else, the grid's border included.
+    pub perimeter: usize,
+    /// Straight runs of perimeter, counted as corners.
+    pub sides: usize,
+    /// The top left and bottom right corners of the smallest box around it.
+    pub bounds: (Point, Point),
+    pub touches_border: bool,
+    /// The labels of the regions inside this one, however deeply nested: the
+    /// ones with no way out to the border (diagonal gaps included) that
+    /// doesn't cross this region.
+    pub encloses: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    /// Each cell's region.
+    pub labels: Grid<usize>,
+    /// In the order their first cell appears, row by row.
+    pub regions: Vec<Region>,
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Labels every group of equal cells that are connected through their
+    /// edges, with a few stats about each.
+    pub fn regions(&self) -> Regions {
+        const UNLABELLED: usize = usize::MAX;
+        let mut labels = Grid::filled(self.n_rows, self.n_cols, UNLABELLED);
+        let mut regions = Vec::new();
+
+        for (row, col) in (0..self.n_rows).flat_map(|r| (0..self.n_cols).map(move |c| (r, c))) {
+            let start = Point::new(row, col);
+            if labels[start] != UNLABELLED {
+                continue;
+            }
+
+            let label = regions.len();
+            let mut region = Region {
+                label,
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+                bounds: (start, start),
+                touches_border: false,
+                encloses: Vec::new(),
+            };
+            labels[start] = label;
+            let mut stack = vec![start];
+            while let Some(p) = stack.pop() {
+                region.area += 1;
+                region.bounds.0 = Point::new(
+                    region.bounds.0.row.min(p.row),
+                    region.bounds.0.col.min(p.col),
+                );
+                region.bounds.1 = Point::new(
+                    region.bounds.1.row.max(p.row),
+                    region.bounds.1.col.max(p.col),
+                );
+                region.touches_border |= p.row == 0
+                    || p.col == 0
+                    || p.row == self.n_rows - 1
+                    || p.col == self.n_cols - 1;
+
+                for direction in Direction::CARDINAL {
+                    match p.step(direction).filter(|&q| self.get(q) == self.get(p)) {
+                        Some(q) if labels[q] == UNLABELLED => {
+                            labels[q] = label;
+                            stack.push(q);
+                        }
+                        Some(_) => {}
+                        None => region.perimeter += 1,
+                    }
+                }
+            }
+            regions.push(region);
+        }
+
+        // Every corner of a region starts a new side. Looking out from a cell
+        // between two edge neighbours, it's an outside corner if neither is in
+        // the region, and an inside one if both are but the cell between them
+        // isn't.
+        for (p, &label) in labels.iter() {
+            let p = Point::from(p);
+            let same = |d: Direction| p.step(d).and_then(|q| labels.get(q)) == Some(&label);
+            for direction in Direction::CARDINAL {
+                let (a, b) = (same(direction), same(direction.turn_right()));
+                if (!a && !b) || (a && b && !same(direction.rotate(1))) {
+                    regions[label].sides += 1;
+                }
+            }
+        }
+
+        for region in regions.iter_mut() {
+            region.encloses = labels.enclosed_by(region);
+        }
+
+        Regions { labels, regions }
+    }
+}
+
+impl Grid<usize> {
+    /// For `Grid::regions`: flood the box around `region`, one cell bigger on
+    /// each side, from its edge. Whatever that can't reach is inside.
+    fn enclosed_by(&self, region: &Region) -> Vec<usize> {
+        let (top_left, bottom_right) = region.bounds;
+        if bottom_right.row - top_left.row < 2 || bottom_right.col - top_left.col < 2 {
+            // Too thin to have an inside.
+            return Vec::new();
+        }
+
+        // Local coordinates are shifted by one, so the ring around the box
+        // (which may be off the grid) starts at 0.
+        let (height, width) = (
+            bottom_right.row - top_left.row + 3,
+            bottom_right.col - top_left.col + 3,
+        );
+        let cell = |p: Point| {
+            let row = (p.row + top_left.row).checked_sub(1)?;
+            let col = (p.col + top_left.col).checked_sub(1)?;
+            self.iloc(row, col)
+        };
+
+        let mut outside = Grid::filled(height, width, false);
+        let origin = Point::new(0, 0);
+        outside[origin] = true;
+        let mut stack = vec![origin];
+        while let Some(p) = stack.pop() {
+            for direction in Direction::ALL {
+                let Some(q) = p.step(direction).filter(|&q| outside.contains(q)) else {
+                    continue;
+                };
+                if !outside[q] && cell(q) != Some(&region.label) {
+                    outside[q] = true;
+                    stack.push(q);
+                }
+            }
+        }
+
+        let mut enclosed: Vec<usize> = outside
+            .iter()
+            .filter(|(_, &reached)| !reached)
+            .filter_map(|(p, _)| cell(Point::from(p)).copied())
+            .filter(|&label| label != region.label)
+            .collect();
+        enclosed.sort();
+        enclosed.dedup();
+        enclosed
+    }
+}
+
+/// Cells keyed by signed `(row, col)`, for things that wander off the edge of
+/// the input. Anything that hasn't been set reads as the default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(isize, isize), T>,
+    default: T,
+    bounds: Option<((isize, isize), (isize, isize))>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            default,
+            bounds: None,
+        }
+    }
+
+    pub fn get(&self, position: (isize, isize)) -> &T {
+        self.cells.get(&position).unwrap_or(&self.default)
+    }
+
+    pub fn set(&mut self, position: (isize, isize), value: T) {
+        self.grow(position);
+        self.cells.insert(position, value);
+    }
+
+    /// Starting from the default if it hasn't been set.
+    pub fn get_mut(&mut self, position: (isize, isize)) -> &mut T
+    where
+        T: Clone,
+    {
+        self.grow(position);
+        self.cells
+            .entry(position)
+            .or_insert_with(|| self.default.clone())
+    }
+
+    /// Puts a cell back to the default. The bounds don't shrink.
+    pub fn remove(&mut self, position: (isize, isize)) -> Option<T> {
+        self.cells.remove(&position)
+    }
+
+    pub fn is_set(&self, position: (isize, isize)) -> bool {
+        self.cells.contains_key(&position)
+    }
+
+    /// How many cells have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The top left and bottom right corners of every cell that's been set,
+    /// or `None` if there aren't any yet.
+    pub fn bounds(&self) -> Option<((isize, isize), (isize, isize))> {
+        self.bounds
+    }
+
+    fn grow(&mut self, (row, col): (isize, isize)) {
+        let ((top, left), (bottom, right)) = self.bounds.unwrap_or(((row, col), (row, col)));
+        self.bounds = Some((
+            (top.min(row), left.min(col)),
+            (bottom.max(row), right.max(col)),
+        ));
+    }
+
+    /// The cells that have been set, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        let mut cells: Vec<_> = self.cells.iter().map(|(&p, value)| (p, value)).collect();
+        cells.sort_by_key(|&(p, _)| p);
+        cells.into_iter()
+    }
+
+    /// Every cell of a grid, placed with its top left at `origin`. Cells equal
+    /// to `default` are left unset.
+    pub fn from_grid(grid: Grid<T>, origin: (isize, isize), default: T) -> SparseGrid<T>
+    where
+        T: PartialEq,
+    {
+        let n_cols = grid.n_cols();
+        let mut sparse = SparseGrid::new(default);
+        for (i, value) in grid.into_values().into_iter().enumerate() {
+            if value != sparse.default {
+                let (row, col) = ((i / n_cols) as isize, (i % n_cols) as isize);
+                sparse.set((origin.0 + row, origin.1 + col), value);
+            }
+        }
+        sparse
+    }
+
+    /// A dense copy of `n_rows` by `n_cols` cells starting at `top_left`.
+    pub fn window(&self, top_left: (isize, isize), n_rows: usize, n_cols: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let values = (0..n_rows as isize)
+            .flat_map(|row| (0..n_cols as isize).map(move |col| (row, col)))
+            .map(|(row, col)| self.get((top_left.0 + row, top_left.1 + col)).clone())
+            .collect();
+        Grid::new(n_rows, n_cols, values)
+    }
+
+    /// A dense copy of everything inside the bounds, and where its top left
+    /// is. `None` if nothing's been set.
+    pub fn to_grid(&self) -> Option<((isize, isize), Grid<T>)>
+    where
+        T: Clone,
+    {
+        let ((top, left), (bottom, right)) = self.bounds?;
+        let n_rows = (bottom - top + 1) as usize;
+        let n_cols = (right - left + 1) as usize;
+        Some(((top, left), self.window((top, left), n_rows, n_cols)))
+    }
+}
+
+impl<T> ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        self.get(p).expect("Point outside the grid.")
+    }
+}
+
+impl<T> ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        self.get_mut(p).expect("Point outside the grid.")
+    }
+}
+
+/// One of the 8 compass directions, with north as up (towards row 0) and
+/// east as right. The cardinal ones are what most grid puzzles move in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Direction {
+    /// Clockwise from north.
+    pub const CARDINAL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    /// Clockwise from north.
+    pub const ALL: [Direction; 8] = [
+        Direction::N,
+        Direction::NE,
+        Direction::E,
+        Direction::SE,
+        Direction::S,
+        Direction::SW,
+        Direction::W,
+        Direction::NW,
+    ];
+
+    /// `eighths` of a full turn clockwise (negative for anticlockwise).
+    pub fn rotate(self, eighths: i32) -> Direction {
+        Direction::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    pub fn turn_right(self) -> Direction {
+        self.rotate(2)
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.rotate(-2)
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as u8 % 2 == 1
+    }
+
+    /// One step this way, as `(d_row, d_col)`.
+    pub fn offset(self) -> Vector {
+        let (row, col) = match self {
+            Direction::N => (-1, 0),
+            Direction::NE => (-1, 1),
+            Direction::E => (0, 1),
+            Direction::SE => (1, 1),
+            Direction::S => (1, 0),
+            Direction::SW => (1, -1),
+            Direction::W => (0, -1),
+            Direction::NW => (-1, -1),
+        };
+        Vector { row, col }
+    }
+
+    /// From an arrow, one of `^`, `v`, `<` or `>`.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::N),
+            'v' => Some(Direction::S),
+            '<' => Some(Direction::W),
+            '>' => Some(Direction::E),
+            _ => None,
+        }
+    }
+
+    /// From a compass letter, one of `N`, `S`, `E` or `W`.
+    pub fn from_letter(c: char) -> Option<Direction> {
+        match c {
+            'N' => Some(Direction::N),
+            'S' => Some(Direction::S),
+            'E' => Some(Direction::E),
+            'W' => Some(Direction::W),
+            _ => None,
+        }
+    }
+}
+
+/// A cell of a grid, counted from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Point {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// The signed difference between two points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vector {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Point {
+    pub const fn new(row: usize, col: usize) -> Point {
+        Point { row, col }
+    }
+
+    /// `None` if that would take us above or left of the grid.
+    pub fn checked_add(self, v: Vector) -> Option<Point> {
+        Some(Point {
+            row: self.row.checked_add_signed(v.row)?,
+            col: self.col.checked_add_signed(v.col)?,
+        })
+    }
+
+    /// The neighbouring point in `direction`, if there is one.
+    pub fn step(self, direction: Direction) -> Option<Point> {
+        self.checked_add(direction.offset())
+    }
+
+    /// Adds `v` on an `n_rows` by `n_cols` torus.
+    pub fn wrapping_add(self, v: Vector, n_rows: usize, n_cols: usize) -> Point {
+        let wrap = |i: usize, d: isize, n: usize| (i as isize + d).rem_euclid(n as isize) as usize;
+        Point {
+            row: wrap(self.row, v.row, n_rows),
+            col: wrap(self.col, v.col, n_cols),
+        }
+    }
+
+    pub fn manhattan(self, other: Point) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+}
+
+impl Vector {
+    pub const fn new(row: isize, col: isize) -> Vector {
+        Vector { row, col }
+    }
+
+    pub fn manhattan(self) -> usize {
+        self.row.unsigned_abs() + self.col.unsigned_abs()
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Point {
+        Point { row, col }
+    }
+}
+
+impl From<Point> for (usize, usize) {
+    fn from(p: Point) -> (usize, usize) {
+        (p.row, p.col)
+    }
+}
+
+impl ops::Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Vector {
+        Vector {
+            row: self.row as isize - other.row as isize,
+            col: self.col as isize - other.col as isize,
+        }
+    }
+}
+
+impl ops::Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl ops::Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl ops::Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Vector {
+        Vector::new(-self.row, -self.col)
+    }
+}
+
+impl ops::Mul<isize> for Vector {
+    type Output = Vector;
+
+    fn mul(self, n: isize) -> Vector {
+        Vector::new(self.row * n, self.col * n)
+    }
+}
+
+/// Why a puzzle input couldn't be parsed, and where. `line` and `column` are
+/// 1-based, and `column` counts characters rather than bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: {}",
+            self.day, self.line, self.column, self.message
+        )?;
+        if self.text.is_empty() {
+            Ok(())
+        } else {
+            // Debug formatting makes a stray space or `\r` visible.
+            write!(f, " (found {:?})", self.text)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// The input a day is parsing, for building `ParseError`s. Everything that
+/// takes a `text` expects a slice of `input` (not a copy of one), since its
+/// position in `input` is how the line and column are found.
+#[derive(Debug, Clone, Copy)]
+pub struct Source<'a> {
+    pub day: u8,
+    pub input: &'a str,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(day: u8, input: &'a str) -> Source<'a> {
+        Source { day, input }
+    }
+
+    pub fn error(&self, text: &str, message: impl Into<String>) -> ParseError {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(self.input.as_ptr() as usize)
+            .filter(|offset| offset + text.len() <= self.input.len())
+            .expect("Error text isn't part of the input.");
+        let before = &self.input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        ParseError {
+            day: self.day,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An empty slice at the very end of the input, for reporting that it
+    /// ended too soon.
+    pub fn end(&self) -> &'a str {
+        &self.input[self.input.len()..]
+    }
+
+    pub fn number<T: FromStr>(&self, text: &str) -> Result<T, ParseError> {
+        text.parse()
+            .map_err(|_| self.error(text, "expected a number"))
+    }
+
+    pub fn split_once(
+        &self,
+        text: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.error(text, format!("expected {delimiter:?}")))
+    }
+
+    /// Splits `text` at its first blank line. Unlike `split_once` with
+    /// `"\n\n"`, this works with `\r\n` line endings too.
+    pub fn split_blank_line(&self, text: &'a str) -> Result<(&'a str, &'a str), ParseError> {
+        let offset = |line: &str| line.as_ptr() as usize - text.as_ptr() as usize;
+        let Some(blank) = text.lines().find(|line| line.is_empty()) else {
+            let first = text.lines().next().unwrap_or(text);
+            return Err(self.error(first, "expected a blank line"));
+        };
+        let before = &text[..offset(blank)];
+        let before = before.strip_suffix('\n').unwrap_or(before);
+        let before = before.strip_suffix('\r').unwrap_or(before);
+        let after = text[offset(blank)..]
+            .find('\n')
+            .map_or(text.len(), |i| offset(blank) + i + 1);
+        Ok((before, &text[after..]))
+    }
+
+    /// The blocks of lines in `text` between blank lines, however its lines
+    /// end.
+    pub fn blocks(&self, text: &'a str) -> Vec<&'a str> {
+        let offset = |line: &str| line.as_ptr() as usize - text.as_ptr() as usize;
+        let mut blocks = Vec::new();
+        let mut start = None;
+        let mut end = 0;
+        for line in text.lines() {
+            if line.is_empty() {
+                if let Some(start) = start.take() {
+                    blocks.push(&text[start..end]);
+                }
+            } else {
+                start.get_or_insert(offset(line));
+                end = offset(line) + line.len();
+            }
+        }
+        if let Some(start) = start {
+            blocks.push(&text[start..end]);
+        }
+        blocks
+    }
+
+    /// For the character at byte `index` of `line`.
+    pub fn unexpected_char(&self, line: &'a str, index: usize) -> ParseError {
+        let c = line[index..]
+            .chars()
+            .next()
+            .expect("Index past the end of the line.");
+        self.error(&line[index..index + c.len_utf8()], "unexpected character")
+    }
+
+    /// Checks that every line of `text` is as long as the first one.
+    pub fn check_rectangular(&self, text: &'a str) -> Result<(), ParseError> {
+        let mut lines = text.lines();
+        let Some(first) = lines.next() else {
+            return Err(self.error(text, "expected at least one row"));
+        };
+        let n_cols = first.chars().count();
+        match lines.find(|line| line.chars().count() != n_cols) {
+            Some(line) => Err(self.error(line, format!("expected {n_cols} columns"))),
+            None => Ok(()),
+        }
+    }
+
+    /// The next item of `iter`, e.g. the next line or field. `after` is what
+    /// came before it, to point at when there isn't one.
+    pub fn next(
+        &self,
+        iter: &mut impl Iterator<Item = &'a str>,
+        after: &'a str,
+        what: &str,
+    ) -> Result<&'a str, ParseError> {
+        iter.next()
+            .ok_or_else(|| self.error(after, format!("expected {what}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Grid, ParseError, Point, Regions, Source, SparseGrid, Vector};
+
+    const INPUT: &str = "\
+3   4
+4   x3
+2   5";
+
+    #[test]
+    fn test_parse_error() {
+        let source = Source::new(1, INPUT);
+        let line = INPUT.lines().nth(1).unwrap();
+        let field = line.split_ascii_whitespace().nth(1).unwrap();
+
+        let error = source.number::<u32>(field).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                day: 1,
+                line: 2,
+                column: 5,
+                text: "x3".to_string(),
+                message: "expected a number".to_string(),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 2, column 5: expected a number (found \"x3\")"
+        );
+
+        let error = source.error(source.end(), "unexpected end of input");
+        assert_eq!((error.line, error.column), (3, 6));
+        assert_eq!(
+            error.to_string(),
+            "day 1, line 3, column 6: unexpected end of input"
+        );
+
+        let error = source.check_rectangular(INPUT).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.text, "4   x3");
+        assert_eq!(source.unexpected_char(line, 4).text, "x");
+    }
+
+    #[test]
+    fn test_blank_lines() {
+        let input = "ab\r\ncd\r\n\r\nef\r\n\r\n\r\ngh\r\n";
+        let source = Source::new(0, input);
+        assert_eq!(source.blocks(input), ["ab\r\ncd", "ef", "gh"]);
+        assert_eq!(
+            source.split_blank_line(input),
+            Ok(("ab\r\ncd", "ef\r\n\r\n\r\ngh\r\n"))
+        );
+        assert_eq!(source.split_blank_line("ab\n\ncd"), Ok(("ab", "cd")));
+
+        let input = "ab\ncd\n";
+        let error = Source::new(0, input).split_blank_line(input).unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (1, "ab"));
+    }
+
+    #[test]
+    fn test_grid_parse() {
+        let input = "#S.\n#.E\n";
+        let source = Source::new(16, input);
+        let (grid, marks) = Grid::parse(&source, input, &['S', 'E'], |c| match c {
+            '#' => Some(false),
+            '.' | 'S' | 'E' => Some(true),
+            _ => None,
+        })
+        .unwrap();
+
+        assert_eq!(grid.values(), [false, true, true, false, true, true]);
+        assert_eq!((grid.n_rows(), grid.n_cols()), (2, 3));
+        assert_eq!(marks.one(&source, 'S', "a start"), Ok((0, 1)));
+        assert_eq!(marks.all('E'), &[(1, 2)]);
+        assert!(marks.all('x').is_empty());
+        assert_eq!(
+            marks.one(&source, 'x', "an x").unwrap_err().message,
+            "expected an x (x)"
+        );
+        assert_eq!(grid.iter().filter(|(_, &open)| open).count(), 4);
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &true)));
+
+        let input = "#S.\n#?E";
+        let error = Grid::parse(&Source::new(16, input), input, &[], |c| {
+            (c != '?').then_some(c)
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "?"));
+
+        let input = "#S.\n#.";
+        let error = Grid::parse(&Source::new(16, input), input, &[], Some).unwrap_err();
+        assert_eq!(error.message, "expected 3 columns");
+    }
+
+    #[test]
+    fn test_grid_edges() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]);
+
+        assert_eq!(grid.up(0, 1), None);
+        assert_eq!(grid.left(1, 0), None);
+        assert_eq!(grid.down(1, 1), None);
+        assert_eq!(grid.right(0, 2), None);
+        assert_eq!(grid.right(usize::MAX, 0), None);
+        assert_eq!(grid.up(1, 2), Some(&3));
+        assert_eq!(grid.left(1, 2), Some(&5));
+
+        let corner: Vec<_> = grid.neighbors(0, 0).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors(1, 1).count(), 3);
+        assert_eq!(grid.neighbors8(0, 0).count(), 3);
+        assert_eq!(grid.neighbors8(1, 1).count(), 5);
+
+        let wrapped: Vec<_> = grid.wrapping_neighbors(0, 0).collect();
+        assert_eq!(wrapped, vec![(1, 0), (0, 1), (1, 0), (0, 2)]);
+        assert_eq!(grid.wrapping_neighbors8(1, 2).count(), 8);
+        assert_eq!(grid.wrapping_offset(0, 0, -5, 7), (1, 1));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::N.turn_right(), Direction::E);
+        assert_eq!(Direction::N.turn_left(), Direction::W);
+        assert_eq!(Direction::W.turn_right(), Direction::N);
+        assert_eq!(Direction::NE.opposite(), Direction::SW);
+        assert_eq!(Direction::NW.rotate(1), Direction::N);
+        assert!(Direction::SE.is_diagonal() && !Direction::S.is_diagonal());
+        assert_eq!(Direction::from_arrow('v'), Some(Direction::S));
+        assert_eq!(Direction::from_letter('W'), Some(Direction::W));
+        assert_eq!(Direction::from_arrow('N'), None);
+        for d in Direction::ALL {
+            assert_eq!(d.offset() + d.opposite().offset(), Vector::default());
+        }
+    }
+
+    #[test]
+    fn test_point() {
+        let p = Point::new(1, 2);
+        assert_eq!(p.step(Direction::N), Some(Point::new(0, 2)));
+        assert_eq!(p.step(Direction::N).unwrap().step(Direction::N), None);
+        assert_eq!(p.checked_add(Vector::new(2, -2)), Some(Point::new(3, 0)));
+        assert_eq!(Point::new(0, 0) - p, Vector::new(-1, -2));
+        assert_eq!(p.checked_add((p - Point::new(4, 4)) * 2), None);
+        assert_eq!(p.wrapping_add(Vector::new(-3, 9), 5, 5), Point::new(3, 1));
+        assert_eq!(p.manhattan(Point::new(4, 0)), 5);
+        assert_eq!((-Vector::new(3, -4)).manhattan(), 7);
+        assert_eq!(<(usize, usize)>::from(p), (1, 2));
+    }
+
+    #[test]
+    fn test_grid_views() {
+        let mut grid = Grid::new(2, 3, vec![1, 2, 3, 4, 5, 6]);
+        let collect = |it: &mut dyn Iterator<Item = &i32>| it.copied().collect::<Vec<_>>();
+
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(collect(&mut grid.column(1)), [2, 5]);
+        assert_eq!(
+            grid.columns()
+                .map(|mut c| collect(&mut c))
+                .collect::<Vec<_>>(),
+            [[1, 4], [2, 5], [3, 6]]
+        );
+        assert_eq!(
+            grid.diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            [vec![4], vec![1, 5], vec![2, 6], vec![3]]
+        );
+        assert_eq!(
+            grid.anti_diagonals()
+                .map(|mut d| collect(&mut d))
+                .collect::<Vec<_>>(),
+            [vec![1], vec![2, 4], vec![3, 5], vec![6]]
+        );
+
+        let column = Grid::new(3, 1, vec![1, 2, 3]);
+        assert_eq!(column.anti_diagonals().count(), 3);
+        assert!(column.anti_diagonals().all(|d| d.count() == 1));
+        assert_eq!(Grid::<u8>::new(0, 0, vec![]).diagonals().count(), 0);
+        // No rows, or rows with nothing in them.
+        let empty = [Grid::<u8>::new(0, 3, vec![]), Grid::new(3, 0, vec![])];
+        for grid in empty.iter() {
+            assert_eq!(grid.diagonals().count(), 0);
+            assert_eq!(grid.anti_diagonals().count(), 0);
+        }
+        let (blank, _) = Grid::parse(&Source::new(0, "\n"), "\n", &[], |_| Some(0)).unwrap();
+        assert_eq!(blank.diagonals().count(), 0);
+        assert_eq!(blank.anti_diagonals().count(), 0);
+
+        grid[Point::new(1, 0)] = 7;
+        assert_eq!(grid.get(Point::new(1, 0)), Some(&7));
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.iloc(0, 3), None);
+        assert_eq!(Grid::filled(2, 2, 0).into_values(), [0; 4]);
+    }
+
+    #[test]
+    fn test_regions() {
+        let input = "\
+AAAAA
+ABBBA
+ABCBA
+ABBBA
+AAAAA
+DDDDD";
+        let (grid, _) = Grid::parse(&Source::new(12, input), input, &[], Some).unwrap();
+        let Regions { labels, regions } = grid.regions();
+
+        assert_eq!(regions.len(), 4);
+        assert_eq!(labels.row(2), [0, 1, 2, 1, 0]);
+        let [a, b, c, d] = [0, 1, 2, 3].map(|i| &regions[i]);
+
+        assert_eq!((a.area, a.perimeter, a.sides), (16, 32, 8));
+        assert_eq!((b.area, b.perimeter, b.sides), (8, 16, 8));
+        assert_eq!((c.area, c.perimeter, c.sides), (1, 4, 4));
+        assert_eq!(b.bounds, (Point::new(1, 1), Point::new(3, 3)));
+        assert!(a.touches_border && !b.touches_border && d.touches_border);
+        assert_eq!(a.encloses, vec![1, 2]);
+        assert_eq!(b.encloses, vec![2]);
+        assert!(c.encloses.is_empty() && d.encloses.is_empty());
+    }
+
+    #[test]
+    fn test_regions_diagonal_gap() {
+        // The inside gets out between the two As that only touch at a corner,
+        // until the corner is filled in.
+        for (corner, n_enclosed) in [('.', 0), ('A', 3)] {
+            let input = format!("AAAAA\nA..BA\nA.BAA\nAAA{corner}.");
+            let (grid, _) = Grid::parse(&Source::new(12, &input), &input, &[], Some).unwrap();
+            let regions = grid.regions().regions;
+            assert_eq!(regions[0].encloses.len(), n_enclosed);
+        }
+    }
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut sparse = SparseGrid::new('.');
+        assert!(sparse.is_empty() && sparse.bounds().is_none());
+        assert!(sparse.to_grid().is_none());
+
+        sparse.set((-1, 2), '#');
+        *sparse.get_mut((1, -1)) = 'x';
+        assert_eq!(*sparse.get((-1, 2)), '#');
+        assert_eq!(*sparse.get((100, 100)), '.');
+        assert_eq!(sparse.bounds(), Some(((-1, -1), (1, 2))));
+        assert_eq!(
+            sparse.iter().collect::<Vec<_>>(),
+            [((-1, 2), &'#'), ((1, -1), &'x')]
+        );
+
+        let (origin, grid) = sparse.to_grid().unwrap();
+        assert_eq!(origin, (-1, -1));
+        assert_eq!(grid.row(0), ['.', '.', '.', '#']);
+        assert_eq!(grid.row(2), ['x', '.', '.', '.']);
+        assert_eq!(SparseGrid::from_grid(grid, origin, '.'), sparse);
+
+        assert_eq!(sparse.window((1, -1), 1, 2).values(), ['x', '.']);
+        assert_eq!(sparse.remove((1, -1)), Some('x'));
+        assert_eq!(sparse.len(), 1);
+        assert!(!sparse.is_set((1, -1)));
     }
 }